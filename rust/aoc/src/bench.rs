@@ -0,0 +1,181 @@
+//! Timing the days over repeated runs.
+//!
+//! Each day is run several times on its input, and the time to parse
+//! and to run each part is summarized by its minimum and median.  The
+//! summaries can be saved as a JSON baseline, and a later run can be
+//! compared against it to see which steps got slower.
+
+use std::error;
+use std::fmt;
+use std::time;
+
+use aoc_solution::{Part, Puzzle};
+use serde_json::json;
+
+/// A step that gets timed: parsing the input, or running one part.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Step {
+    Parse,
+    Run(Part),
+}
+
+impl fmt::Display for Step {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Step::Parse => write!(f, "parse"),
+            Step::Run(part) => write!(f, "part {}", part),
+        }
+    }
+}
+
+/// The fastest and the median of the times for one step.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub min: time::Duration,
+    pub median: time::Duration,
+}
+
+impl Stats {
+    /// Summarizes the times, which must not be empty.  With an even
+    /// number of times, the median is halfway between the middle two.
+    pub fn of(times: &[time::Duration]) -> Stats {
+        let mut sorted = times.to_vec();
+        sorted.sort();
+        let middle = sorted.len() / 2;
+        let median = if sorted.len().is_multiple_of(2) {
+            (sorted[middle - 1] + sorted[middle]) / 2
+        } else {
+            sorted[middle]
+        };
+        Stats { min: sorted[0], median }
+    }
+}
+
+#[test]
+fn test_stats() {
+    let ms = time::Duration::from_millis;
+    assert_eq!(Stats::of(&[ms(5), ms(1), ms(3)]), Stats { min: ms(1), median: ms(3) });
+    assert_eq!(Stats::of(&[ms(8), ms(2), ms(4), ms(1)]), Stats { min: ms(1), median: ms(3) });
+}
+
+/// The summary of the times for one step of one day.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Timing {
+    pub day: u32,
+    pub step: Step,
+    pub stats: Stats,
+}
+
+/// Runs one day on the text of its input the given number of times,
+/// and summarizes the time taken by each step.
+pub fn bench(puzzle: &dyn Puzzle, text: &str, parts: &[Part], runs: usize) -> Result<Vec<Timing>, Box<dyn error::Error>> {
+    let mut parse_times = Vec::new();
+    let mut part_times = vec![Vec::new(); parts.len()];
+    for _ in 0..runs {
+        let report = puzzle.run(text, parts)?;
+        parse_times.push(report.parse_time);
+        for (times, timed) in part_times.iter_mut().zip(report.parts) {
+            times.push(timed.elapsed);
+        }
+    }
+    let day = puzzle.day();
+    let mut result = vec![Timing { day, step: Step::Parse, stats: Stats::of(&parse_times) }];
+    for (&part, times) in parts.iter().zip(part_times) {
+        result.push(Timing { day, step: Step::Run(part), stats: Stats::of(&times) });
+    }
+    Ok(result)
+}
+
+fn millis(duration: time::Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The JSON baseline for the timings, with the times in milliseconds.
+pub fn to_json(runs: usize, timings: &[Timing]) -> String {
+    let entries: Vec<serde_json::Value> = timings.iter()
+        .map(|t| json!({
+            "day": t.day,
+            "step": t.step.to_string(),
+            "min_ms": millis(t.stats.min),
+            "median_ms": millis(t.stats.median),
+        }))
+        .collect();
+    serde_json::to_string_pretty(&json!({ "runs": runs, "timings": entries })).unwrap()
+}
+
+/// The median time of one step in a baseline.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Baseline {
+    pub day: u32,
+    pub step: String,
+    pub median_ms: f64,
+}
+
+/// Reads the medians back out of a JSON baseline.
+pub fn from_json(text: &str) -> Result<Vec<Baseline>, String> {
+    let value: serde_json::Value = serde_json::from_str(text).map_err(|e| e.to_string())?;
+    let entries = value["timings"].as_array().ok_or("expected a list of timings")?;
+    entries.iter()
+        .map(|entry| {
+            let day = entry["day"].as_u64().ok_or("expected a day in each timing")?;
+            let step = entry["step"].as_str().ok_or("expected a step in each timing")?;
+            let median_ms = entry["median_ms"].as_f64().ok_or("expected median_ms in each timing")?;
+            Ok(Baseline { day: day as u32, step: String::from(step), median_ms })
+        })
+        .collect()
+}
+
+/// Steps that change by less than this many milliseconds are never
+/// reported as slower, because the difference is mostly noise.
+const NOISE_MS: f64 = 0.5;
+
+/// How a timing compares to the baseline.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Change {
+    pub baseline_ms: f64,
+    /// How much slower, as a percentage of the baseline; negative if
+    /// it got faster.
+    pub percent: f64,
+    /// True if it is slower by more than the tolerance.
+    pub slower: bool,
+}
+
+/// Compares a timing to the same step in the baseline, if it's there.
+/// A step is slower if its median went up by more than `tolerance`
+/// percent.
+pub fn compare(timing: &Timing, baseline: &[Baseline], tolerance: f64) -> Option<Change> {
+    let step = timing.step.to_string();
+    let base = baseline.iter().find(|b| b.day == timing.day && b.step == step)?;
+    let median_ms = millis(timing.stats.median);
+    let percent = if base.median_ms > 0.0 { (median_ms - base.median_ms) / base.median_ms * 100.0 } else { 0.0 };
+    let slower = percent > tolerance && median_ms - base.median_ms > NOISE_MS;
+    Some(Change { baseline_ms: base.median_ms, percent, slower })
+}
+
+#[test]
+fn test_baseline() {
+    let ms = time::Duration::from_millis;
+    let us = time::Duration::from_micros;
+    let timings = vec![
+        Timing { day: 23, step: Step::Parse, stats: Stats { min: us(100), median: us(200) } },
+        Timing { day: 23, step: Step::Run(Part::Two), stats: Stats { min: ms(100), median: ms(110) } },
+    ];
+    let baseline = from_json(&to_json(5, &timings)).unwrap();
+    assert_eq!(
+        baseline,
+        vec![
+            Baseline { day: 23, step: String::from("parse"), median_ms: 0.2 },
+            Baseline { day: 23, step: String::from("part 2"), median_ms: 110.0 },
+        ]
+    );
+
+    let later = |step, median| Timing { day: 23, step, stats: Stats { min: median, median } };
+    let change = compare(&later(Step::Run(Part::Two), ms(143)), &baseline, 20.0).unwrap();
+    assert_eq!((change.baseline_ms, change.percent.round(), change.slower), (110.0, 30.0, true));
+    assert!(! compare(&later(Step::Run(Part::Two), ms(120)), &baseline, 20.0).unwrap().slower);
+    // Too small a change to count, even though it's 50% slower.
+    assert!(! compare(&later(Step::Parse, us(300)), &baseline, 20.0).unwrap().slower);
+    assert_eq!(compare(&later(Step::Run(Part::One), ms(3)), &baseline, 20.0), None);
+
+    assert_eq!(from_json("{\"runs\": 5}"), Err(String::from("expected a list of timings")));
+}
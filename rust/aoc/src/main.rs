@@ -0,0 +1,398 @@
+//! Runs the solution for any of the days, and times it.
+//!
+//! Every day implements `aoc_solution::Solution`, so reading the
+//! input, running the parts, and reporting the answers work the same
+//! way for all of them.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::process;
+use std::time;
+
+use aoc_solution::{Part, Puzzle};
+
+mod bench;
+mod manifest;
+mod results;
+
+const USAGE: &str = "\
+usage: aoc run [--day N] [--part P] [--input FILE] [--json]
+       aoc bench [--day N] [--part P] [--input FILE] [--runs N]
+                 [--save FILE] [--compare FILE]
+       aoc list
+
+commands:
+  run     print the answers, and how long each part took
+  bench   run each day several times, and print the fastest and median
+          times for parsing and for each part
+  list    print the days that have solutions
+
+--day N picks one day; without it, every day is run.
+--part P picks part 1 or 2; without it, both parts are run.
+--input FILE reads the input from FILE instead of input/dayNN-input.txt,
+and needs --day.
+--json prints one line of JSON for each part, with the answer, the
+input file, the times, and whether the answer matches the known one
+in answers.txt.
+--runs N runs each day N times; the default is 5.
+--save FILE writes the times to FILE as a JSON baseline.
+--compare FILE compares the times to a baseline saved earlier, and
+fails if any step's median is more than 20% slower.";
+
+/// How many times `bench` runs each day, unless told otherwise.
+const DEFAULT_RUNS: usize = 5;
+
+/// How much slower than the baseline, in percent, a step can get
+/// before `bench --compare` fails.
+const TOLERANCE: f64 = 20.0;
+
+/// The solutions for all of the days, in order.
+fn puzzles() -> Vec<Box<dyn Puzzle>> {
+    vec![
+        Box::new(day_13::Day13),
+        Box::new(day_14::Day14),
+        Box::new(day_15::Day15),
+        Box::new(day_16::Day16),
+        Box::new(day_17::Day17),
+        Box::new(day_18::Day18),
+        Box::new(day_19::Day19),
+        Box::new(day_20::Day20),
+        Box::new(day_21::Day21),
+        Box::new(day_22::Day22),
+        Box::new(day_23::Day23),
+        Box::new(day_24::Day24),
+        Box::new(day_25::Day25),
+    ]
+}
+
+#[test]
+fn test_puzzles_are_in_order() {
+    let days: Vec<u32> = puzzles().iter().map(|p| p.day()).collect();
+    assert_eq!(days, (13..=25).collect::<Vec<u32>>());
+}
+
+/// Runs each day on the inputs in the manifest, and returns a message
+/// for every answer that isn't the known one.
+#[cfg(test)]
+fn check_known_answers(slow: bool) -> Vec<String> {
+    let dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    let all = puzzles();
+    let mut failures = Vec::new();
+    for known in manifest::read(&dir).unwrap().into_iter().filter(|k| k.slow == slow) {
+        let puzzle = all.iter().find(|p| p.day() == known.day).unwrap();
+        let text = aoc_common::read_file(dir.join(&known.input)).unwrap();
+        let answer = match puzzle.run(&text, &[known.part]) {
+            Ok(report) => report.parts[0].answer.to_string(),
+            Err(e) => format!("error: {}", e),
+        };
+        if answer != known.answer {
+            failures.push(format!(
+                "day {} part {} on {}: expected {}, got {}",
+                known.day, known.part, known.input, known.answer, answer
+            ));
+        }
+    }
+    failures
+}
+
+#[test]
+fn test_known_answers() {
+    assert_eq!(check_known_answers(false), Vec::<String>::new());
+}
+
+/// The answers that take a long time to find; run with
+/// `cargo test -p aoc --release -- --ignored`.
+#[test]
+#[ignore]
+fn test_slow_known_answers() {
+    assert_eq!(check_known_answers(true), Vec::<String>::new());
+}
+
+/// The input file for a day, if one isn't given on the command line.
+fn default_input(day: u32) -> String {
+    format!("input/day{:02}-input.txt", day)
+}
+
+/// What the command line asks for.
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    List,
+    Run { day: Option<u32>, parts: Vec<Part>, input: Option<String>, json: bool },
+    Bench {
+        day: Option<u32>,
+        parts: Vec<Part>,
+        input: Option<String>,
+        runs: usize,
+        save: Option<String>,
+        compare: Option<String>,
+    },
+}
+
+/// Parses the command line arguments, not including the program name.
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut day = None;
+    let mut parts = Part::BOTH.to_vec();
+    let mut input = None;
+    let mut runs = None;
+    let mut save = None;
+    let mut compare = None;
+    let mut json = false;
+    let mut words = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--day" {
+            let value = iter.next().ok_or("--day needs a number")?;
+            day = Some(value.parse().map_err(|_| format!("--day needs a number, not {:?}", value))?);
+        } else if arg == "--part" {
+            let value = iter.next().ok_or("--part needs 1 or 2")?;
+            parts = vec![value.parse()?];
+        } else if arg == "--input" {
+            input = Some(iter.next().ok_or("--input needs a file name")?.clone());
+        } else if arg == "--runs" {
+            let value = iter.next().ok_or("--runs needs a number")?;
+            match value.parse() {
+                Ok(n) if n > 0 => runs = Some(n),
+                _ => return Err(format!("--runs needs a number more than 0, not {:?}", value)),
+            }
+        } else if arg == "--save" {
+            save = Some(iter.next().ok_or("--save needs a file name")?.clone());
+        } else if arg == "--json" {
+            json = true;
+        } else if arg == "--compare" {
+            compare = Some(iter.next().ok_or("--compare needs a file name")?.clone());
+        } else if arg.starts_with("--") {
+            return Err(format!("unknown option {:?}", arg));
+        } else {
+            words.push(arg.as_str());
+        }
+    }
+    if input.is_some() && day.is_none() {
+        return Err(String::from("--input needs --day"));
+    }
+    if words.as_slice() != ["bench"] {
+        let bench_options = [("--runs", runs.is_some()), ("--save", save.is_some()), ("--compare", compare.is_some())];
+        if let Some((option, _)) = bench_options.iter().find(|(_, given)| *given) {
+            return Err(format!("{} is only for bench", option));
+        }
+    }
+    if json && words.as_slice() != ["run"] {
+        return Err(String::from("--json is only for run"));
+    }
+    match words.as_slice() {
+        ["run"] => Ok(Command::Run { day, parts, input, json }),
+        ["bench"] => Ok(Command::Bench { day, parts, input, runs: runs.unwrap_or(DEFAULT_RUNS), save, compare }),
+        ["list"] => Ok(Command::List),
+        [] => Err(String::from("no command given")),
+        _ => Err(format!("bad command {:?}", words.join(" "))),
+    }
+}
+
+#[test]
+fn test_parse_args() {
+    let args = |text: &str| -> Vec<String> { text.split_whitespace().map(String::from).collect() };
+    assert_eq!(parse_args(&args("list")), Ok(Command::List));
+    assert_eq!(parse_args(&args("run")), Ok(Command::Run { day: None, parts: Part::BOTH.to_vec(), input: None, json: false }));
+    assert_eq!(
+        parse_args(&args("run --day 16 --part 2 --input a.txt --json")),
+        Ok(Command::Run { day: Some(16), parts: vec![Part::Two], input: Some(String::from("a.txt")), json: true })
+    );
+    assert_eq!(parse_args(&args("bench --json")), Err(String::from("--json is only for run")));
+    assert_eq!(parse_args(&args("run --part 3")), Err(String::from("there is no part \"3\"")));
+    assert_eq!(parse_args(&args("run --day x")), Err(String::from("--day needs a number, not \"x\"")));
+    assert_eq!(parse_args(&args("run --input a.txt")), Err(String::from("--input needs --day")));
+    assert_eq!(parse_args(&args("run --fast")), Err(String::from("unknown option \"--fast\"")));
+    assert_eq!(parse_args(&args("")), Err(String::from("no command given")));
+    assert_eq!(parse_args(&args("fly away")), Err(String::from("bad command \"fly away\"")));
+    assert_eq!(
+        parse_args(&args("bench --day 23 --runs 3 --compare base.json")),
+        Ok(Command::Bench {
+            day: Some(23),
+            parts: Part::BOTH.to_vec(),
+            input: None,
+            runs: 3,
+            save: None,
+            compare: Some(String::from("base.json")),
+        })
+    );
+    assert_eq!(
+        parse_args(&args("bench --save base.json")),
+        Ok(Command::Bench { day: None, parts: Part::BOTH.to_vec(), input: None, runs: 5, save: Some(String::from("base.json")), compare: None })
+    );
+    assert_eq!(parse_args(&args("bench --runs 0")), Err(String::from("--runs needs a number more than 0, not \"0\"")));
+    assert_eq!(parse_args(&args("run --save base.json")), Err(String::from("--save is only for bench")));
+}
+
+fn format_duration(duration: time::Duration) -> String {
+    format!("{:.3} ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// Runs one day, and prints the answers.  Returns a message saying
+/// what went wrong if the input couldn't be read or parsed.
+fn run_day(puzzle: &dyn Puzzle, parts: &[Part], file_name: &str) -> Result<(), String> {
+    let text = aoc_common::read_file(file_name).map_err(|e| e.to_string())?;
+    let report = puzzle.run(&text, parts).map_err(|e| format!("{}: {}", file_name, e))?;
+    println!("Day {}: {} (parsed in {})", report.day, report.title, format_duration(report.parse_time));
+    for timed in report.parts {
+        println!("  Part {}: {} ({})", timed.part, timed.answer, format_duration(timed.elapsed));
+    }
+    Ok(())
+}
+
+/// Runs one day, and prints a line of JSON for each part.  Returns
+/// false if the input couldn't be read or parsed, or an answer isn't
+/// the known one.
+fn run_day_json(puzzle: &dyn Puzzle, parts: &[Part], file_name: &str, known: &[manifest::Known]) -> bool {
+    let report = aoc_common::read_file(file_name)
+        .map_err(|e| e.to_string())
+        .and_then(|text| puzzle.run(&text, parts).map_err(|e| e.to_string()));
+    match report {
+        Ok(report) => {
+            let passed = report.parts.iter()
+                .all(|timed| results::status(&report, timed, file_name, known) != results::Status::Fail);
+            for line in results::report_lines(&report, file_name, known) {
+                println!("{}", line);
+            }
+            passed
+        },
+        Err(message) => {
+            for line in results::error_lines(puzzle, parts, file_name, &message) {
+                println!("{}", line);
+            }
+            false
+        },
+    }
+}
+
+/// The known answers in the manifest in the current directory, or
+/// none if there isn't one.
+fn known_answers() -> Vec<manifest::Known> {
+    match manifest::read(".") {
+        Ok(known) => known,
+        Err(e) if matches!(&e.kind, aoc_common::ErrorKind::Io(io_error) if io_error.kind() == io::ErrorKind::NotFound) => {
+            Vec::new()
+        },
+        Err(e) => {
+            eprintln!("aoc: {}", e);
+            process::exit(1);
+        },
+    }
+}
+
+/// Times one day, and prints the fastest and median times for each
+/// step, and how they compare to the baseline if there is one.
+/// Returns the timings, and whether any step got slower.
+fn bench_day(
+    puzzle: &dyn Puzzle,
+    parts: &[Part],
+    file_name: &str,
+    runs: usize,
+    baseline: Option<&[bench::Baseline]>,
+) -> Result<(Vec<bench::Timing>, bool), String> {
+    let text = aoc_common::read_file(file_name).map_err(|e| e.to_string())?;
+    let timings = bench::bench(puzzle, &text, parts, runs).map_err(|e| format!("{}: {}", file_name, e))?;
+    println!("Day {}: {} ({} runs)", puzzle.day(), puzzle.title(), runs);
+    let mut slower = false;
+    for timing in timings.iter() {
+        print!(
+            "  {:<8} min {:>12}  median {:>12}",
+            timing.step.to_string(),
+            format_duration(timing.stats.min),
+            format_duration(timing.stats.median)
+        );
+        if let Some(change) = baseline.and_then(|b| bench::compare(timing, b, TOLERANCE)) {
+            print!("  was {:.3} ms ({:+.0}%)", change.baseline_ms, change.percent);
+            if change.slower {
+                print!("  SLOWER");
+                slower = true;
+            }
+        }
+        println!();
+    }
+    Ok((timings, slower))
+}
+
+/// The puzzles that the command line picked, or all of them.  Exits
+/// if there isn't a solution for the day asked for.
+fn chosen_puzzles(day: Option<u32>) -> Vec<Box<dyn Puzzle>> {
+    let chosen: Vec<Box<dyn Puzzle>> = puzzles().into_iter().filter(|p| day.is_none() || day == Some(p.day())).collect();
+    if chosen.is_empty() {
+        eprintln!("aoc: there is no solution for day {}", day.unwrap());
+        process::exit(2);
+    }
+    chosen
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help" || a == "-h" || a == "help") {
+        println!("{}", USAGE);
+        return;
+    }
+    let command = parse_args(&args).unwrap_or_else(|message| {
+        eprintln!("aoc: {}\n\n{}", message, USAGE);
+        process::exit(2);
+    });
+    match command {
+        Command::List => {
+            for puzzle in puzzles() {
+                println!("{:2} {}", puzzle.day(), puzzle.title());
+            }
+        },
+        Command::Run { day, parts, input, json } => {
+            let known = if json { known_answers() } else { Vec::new() };
+            let mut failed = false;
+            for puzzle in chosen_puzzles(day) {
+                let file_name = input.clone().unwrap_or_else(|| default_input(puzzle.day()));
+                if json {
+                    failed = ! run_day_json(puzzle.as_ref(), &parts, &file_name, &known) || failed;
+                } else if let Err(message) = run_day(puzzle.as_ref(), &parts, &file_name) {
+                    eprintln!("aoc: {}", message);
+                    failed = true;
+                }
+            }
+            if failed {
+                process::exit(1);
+            }
+        },
+        Command::Bench { day, parts, input, runs, save, compare } => {
+            let baseline = compare.map(|file_name| {
+                fs::read_to_string(&file_name)
+                    .map_err(|e| e.to_string())
+                    .and_then(|text| bench::from_json(&text))
+                    .unwrap_or_else(|message| {
+                        eprintln!("aoc: {}: {}", file_name, message);
+                        process::exit(1);
+                    })
+            });
+            let mut failed = false;
+            let mut slower = false;
+            let mut all_timings = Vec::new();
+            for puzzle in chosen_puzzles(day) {
+                let file_name = input.clone().unwrap_or_else(|| default_input(puzzle.day()));
+                match bench_day(puzzle.as_ref(), &parts, &file_name, runs, baseline.as_deref()) {
+                    Ok((timings, day_slower)) => {
+                        all_timings.extend(timings);
+                        slower = slower || day_slower;
+                    },
+                    Err(message) => {
+                        eprintln!("aoc: {}", message);
+                        failed = true;
+                    },
+                }
+            }
+            if let Some(file_name) = save {
+                if let Err(e) = fs::write(&file_name, bench::to_json(runs, &all_timings) + "\n") {
+                    eprintln!("aoc: {}: {}", file_name, e);
+                    failed = true;
+                }
+            }
+            if slower {
+                eprintln!("aoc: some steps are more than {}% slower than the baseline", TOLERANCE);
+            }
+            if failed || slower {
+                process::exit(1);
+            }
+        },
+    }
+}
@@ -0,0 +1,85 @@
+//! The manifest of known answers, in `answers.txt`.
+//!
+//! Each line names a day, a part, and an input file, and gives the
+//! answer for that input.  Blank lines and lines starting with `#` are
+//! skipped.
+
+use std::path;
+
+use aoc_common::{InputError, Line};
+use aoc_solution::Part;
+
+/// The name of the manifest, in the directory the input paths are
+/// relative to.
+pub const FILE_NAME: &str = "answers.txt";
+
+/// The answer to one part of one day, for one input file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Known {
+    pub day: u32,
+    pub part: Part,
+    /// The input file, relative to the directory of the manifest.
+    pub input: String,
+    pub answer: String,
+    /// True if finding the answer takes too long for a normal test
+    /// run.
+    pub slow: bool,
+}
+
+fn parse_known(line: &Line) -> Result<Known, InputError> {
+    let words: Vec<&str> = line.text.split_whitespace().collect();
+    let slow = match words.len() {
+        4 => false,
+        5 if words[4] == "slow" => true,
+        _ => return Err(line.invalid(format!("expected \"day part input answer [slow]\", found {:?}", line.text))),
+    };
+    Ok(Known {
+        day: aoc_common::parse_number(words[0], line.number)?,
+        part: words[1].parse().map_err(|message: String| line.invalid(message))?,
+        input: String::from(words[2]),
+        answer: String::from(words[3]),
+        slow,
+    })
+}
+
+/// Parses the text of a manifest.
+pub fn parse(text: &str) -> Result<Vec<Known>, InputError> {
+    aoc_common::lines(text)
+        .filter(|line| ! line.text.trim_start().starts_with('#'))
+        .map(|line| parse_known(&line))
+        .collect()
+}
+
+/// Reads the manifest in the given directory.
+pub fn read<P: AsRef<path::Path>>(dir: P) -> Result<Vec<Known>, InputError> {
+    let file_name = dir.as_ref().join(FILE_NAME);
+    let text = aoc_common::read_file(&file_name)?;
+    parse(&text).map_err(|e| e.in_file(&file_name))
+}
+
+/// The known answer for a part of a day on an input file, if there
+/// is one.
+pub fn answer_for<'a>(known: &'a [Known], day: u32, part: Part, input: &str) -> Option<&'a str> {
+    known.iter()
+        .find(|k| k.day == day && k.part == part && path::Path::new(&k.input) == path::Path::new(input))
+        .map(|k| k.answer.as_str())
+}
+
+#[test]
+fn test_parse() {
+    let known = parse("# day part input answer\n\n13 1 a.txt 295\n21 2 b.txt x,y slow\n").unwrap();
+    assert_eq!(
+        known,
+        vec![
+            Known { day: 13, part: Part::One, input: String::from("a.txt"), answer: String::from("295"), slow: false },
+            Known { day: 21, part: Part::Two, input: String::from("b.txt"), answer: String::from("x,y"), slow: true },
+        ]
+    );
+    assert_eq!(answer_for(&known, 21, Part::Two, "b.txt"), Some("x,y"));
+    assert_eq!(answer_for(&known, 21, Part::One, "b.txt"), None);
+    assert_eq!(parse("13 3 a.txt 1\n").unwrap_err().to_string(), "line 1: there is no part \"3\"");
+    assert_eq!(
+        parse("13 1 a.txt\n").unwrap_err().to_string(),
+        "line 1: expected \"day part input answer [slow]\", found \"13 1 a.txt\""
+    );
+}
@@ -0,0 +1,131 @@
+//! The results as JSON lines, for dashboards and other programs to
+//! read.
+//!
+//! Each line is one part of one day, with the answer as a string, the
+//! input file, how long it took, and whether the answer matches the
+//! known answer in the manifest.
+
+use std::fmt;
+use std::time;
+
+use aoc_solution::{Answer, Part, Puzzle, Report, Timed};
+use serde_json::json;
+
+use crate::manifest;
+
+/// How an answer compares to the known answer.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+    Pass,
+    Fail,
+    /// There is no known answer for this input.
+    Unknown,
+    /// The input couldn't be read or parsed.
+    Error,
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Status::Pass => write!(f, "pass"),
+            Status::Fail => write!(f, "fail"),
+            Status::Unknown => write!(f, "unknown"),
+            Status::Error => write!(f, "error"),
+        }
+    }
+}
+
+fn millis(duration: time::Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// The answer as a string, or null for a part with no puzzle.
+fn answer_json(answer: &Answer) -> serde_json::Value {
+    match answer {
+        Answer::None => serde_json::Value::Null,
+        _ => json!(answer.to_string()),
+    }
+}
+
+/// How the answer to one part of a report compares to the known
+/// answer for the input.
+pub fn status(report: &Report, timed: &Timed, input: &str, known: &[manifest::Known]) -> Status {
+    match manifest::answer_for(known, report.day, timed.part, input) {
+        None => Status::Unknown,
+        Some(answer) if answer == timed.answer.to_string() => Status::Pass,
+        Some(_) => Status::Fail,
+    }
+}
+
+/// One line for each part that was run.
+pub fn report_lines(report: &Report, input: &str, known: &[manifest::Known]) -> Vec<String> {
+    report.parts.iter()
+        .map(|timed| {
+            json!({
+                "day": report.day,
+                "title": report.title,
+                "part": timed.part.number(),
+                "input": input,
+                "answer": answer_json(&timed.answer),
+                "expected": manifest::answer_for(known, report.day, timed.part, input),
+                "status": status(report, timed, input, known).to_string(),
+                "parse_ms": millis(report.parse_time),
+                "elapsed_ms": millis(timed.elapsed),
+            })
+            .to_string()
+        })
+        .collect()
+}
+
+/// One line for each part that was asked for, when the input couldn't
+/// be read or parsed.
+pub fn error_lines(puzzle: &dyn Puzzle, parts: &[Part], input: &str, message: &str) -> Vec<String> {
+    parts.iter()
+        .map(|part| {
+            json!({
+                "day": puzzle.day(),
+                "title": puzzle.title(),
+                "part": part.number(),
+                "input": input,
+                "answer": null,
+                "status": Status::Error.to_string(),
+                "error": message,
+            })
+            .to_string()
+        })
+        .collect()
+}
+
+#[test]
+fn test_report_lines() {
+    let known = manifest::parse("21 1 a.txt 5\n21 2 a.txt x,y\n").unwrap();
+    let timed = |part, answer: &str| Timed {
+        part,
+        answer: Answer::from(answer),
+        elapsed: time::Duration::from_micros(1500),
+    };
+    let report = Report {
+        day: 21,
+        title: "Allergen Assessment",
+        parse_time: time::Duration::from_millis(2),
+        parts: vec![timed(Part::One, "5"), timed(Part::Two, "x,z")],
+    };
+    let lines: Vec<serde_json::Value> =
+        report_lines(&report, "a.txt", &known).iter().map(|line| serde_json::from_str(line).unwrap()).collect();
+    assert_eq!(
+        lines,
+        vec![
+            json!({
+                "day": 21, "title": "Allergen Assessment", "part": 1, "input": "a.txt",
+                "answer": "5", "expected": "5", "status": "pass", "parse_ms": 2.0, "elapsed_ms": 1.5,
+            }),
+            json!({
+                "day": 21, "title": "Allergen Assessment", "part": 2, "input": "a.txt",
+                "answer": "x,z", "expected": "x,y", "status": "fail", "parse_ms": 2.0, "elapsed_ms": 1.5,
+            }),
+        ]
+    );
+
+    let other: serde_json::Value = serde_json::from_str(&report_lines(&report, "b.txt", &known)[0]).unwrap();
+    assert_eq!((&other["expected"], &other["status"]), (&json!(null), &json!("unknown")));
+}
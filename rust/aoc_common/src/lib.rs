@@ -0,0 +1,311 @@
+//! Reading puzzle input.
+//!
+//! The input files are all made of lines, often split into sections
+//! by blank lines, and the lines hold numbers, lists of numbers, or
+//! rows of a grid.  The readers here split the text up the same way
+//! for every day, and keep track of line numbers, so that a problem
+//! in an input file is reported with where it is.
+
+use std::error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path;
+use std::str;
+
+/// What was wrong with the input.
+#[derive(Debug)]
+pub enum ErrorKind {
+    /// The file couldn't be read.
+    Io(io::Error),
+    /// Text that should have been a number.
+    BadNumber(String),
+    /// A character that isn't allowed where it is.
+    UnexpectedChar(char),
+    /// A row of a grid that isn't as wide as the first row.
+    RaggedRow { expected: usize, found: usize },
+    /// The wrong number of blank-line-separated sections.
+    SectionCount { expected: usize, found: usize },
+    /// The input ended before something that should be there.
+    Missing(String),
+    /// Anything else, described for the person reading the message.
+    Invalid(String),
+}
+
+/// A problem with the input, and where it is.
+#[derive(Debug)]
+pub struct InputError {
+    /// The file, if the text came from `read_file`.
+    pub file: Option<path::PathBuf>,
+    /// The line number, starting at 1, or 0 if the problem isn't on
+    /// one line.
+    pub line: usize,
+    pub kind: ErrorKind,
+}
+
+impl InputError {
+    pub fn new(line: usize, kind: ErrorKind) -> InputError {
+        InputError { file: None, line, kind }
+    }
+
+    /// Says which file the error is in.
+    pub fn in_file<P: AsRef<path::Path>>(mut self, file: P) -> InputError {
+        self.file = Some(file.as_ref().to_path_buf());
+        self
+    }
+}
+
+impl fmt::Display for InputError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}: ", file.display())?;
+        }
+        if self.line != 0 {
+            write!(f, "line {}: ", self.line)?;
+        }
+        match &self.kind {
+            ErrorKind::Io(e) => write!(f, "{}", e),
+            ErrorKind::BadNumber(text) => write!(f, "expected a number, found {:?}", text),
+            ErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+            ErrorKind::RaggedRow { expected, found } => {
+                write!(f, "expected a row {} wide, found one {} wide", expected, found)
+            },
+            ErrorKind::SectionCount { expected, found } => {
+                write!(f, "expected {} sections separated by blank lines, found {}", expected, found)
+            },
+            ErrorKind::Missing(what) => write!(f, "missing {}", what),
+            ErrorKind::Invalid(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl error::Error for InputError {}
+
+/// Reads a whole input file.
+pub fn read_file<P: AsRef<path::Path>>(file: P) -> Result<String, InputError> {
+    fs::read_to_string(file.as_ref()).map_err(|e| InputError::new(0, ErrorKind::Io(e)).in_file(file))
+}
+
+/// One line of the input, and where it is.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Line<'a> {
+    /// The line number, starting at 1.
+    pub number: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Line<'a> {
+    /// An error on this line.
+    pub fn error(&self, kind: ErrorKind) -> InputError {
+        InputError::new(self.number, kind)
+    }
+
+    /// An error on this line, described by the message.
+    pub fn invalid<S: Into<String>>(&self, message: S) -> InputError {
+        self.error(ErrorKind::Invalid(message.into()))
+    }
+
+    /// Parses the whole line, without surrounding spaces, as a number.
+    pub fn parse<T: str::FromStr>(&self) -> Result<T, InputError> {
+        parse_number(self.text.trim(), self.number)
+    }
+
+    /// Parses a list of numbers with the separator between them, like
+    /// "1,2,3".
+    pub fn number_list<T: str::FromStr>(&self, separator: &str) -> Result<Vec<T>, InputError> {
+        number_list(self.text, separator, self.number)
+    }
+}
+
+/// Parses a number, for an error on the given line if it isn't one.
+pub fn parse_number<T: str::FromStr>(text: &str, line: usize) -> Result<T, InputError> {
+    text.parse::<T>().map_err(|_| InputError::new(line, ErrorKind::BadNumber(String::from(text))))
+}
+
+/// Parses a list of numbers with the separator between them, and
+/// spaces allowed around them.
+pub fn number_list<T: str::FromStr>(text: &str, separator: &str, line: usize) -> Result<Vec<T>, InputError> {
+    text.trim().split(separator).map(|s| parse_number(s.trim(), line)).collect()
+}
+
+/// The lines of the text that aren't empty, numbered from 1.
+pub fn lines(text: &str) -> impl Iterator<Item = Line<'_>> {
+    numbered_lines(text, 1)
+}
+
+fn numbered_lines(text: &str, first_line: usize) -> impl Iterator<Item = Line<'_>> {
+    text.lines()
+        .enumerate()
+        .map(move |(index, text)| Line { number: first_line + index, text })
+        .filter(|line| !line.text.trim().is_empty())
+}
+
+/// A part of the input between blank lines.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Section<'a> {
+    /// The line number of the first line, starting at 1.
+    pub first_line: usize,
+    pub text: &'a str,
+}
+
+impl<'a> Section<'a> {
+    /// The lines of the section, with their line numbers in the
+    /// whole input.
+    pub fn lines(&self) -> impl Iterator<Item = Line<'a>> {
+        numbered_lines(self.text, self.first_line)
+    }
+
+    /// The first line of the section, which is often a header.
+    pub fn first(&self) -> Line<'a> {
+        self.lines().next().unwrap()
+    }
+
+    /// The lines after the first one.
+    pub fn rest(&self) -> impl Iterator<Item = Line<'a>> {
+        self.lines().skip(1)
+    }
+
+    /// An error in the section as a whole, reported at its first line.
+    pub fn error(&self, kind: ErrorKind) -> InputError {
+        InputError::new(self.first_line, kind)
+    }
+}
+
+/// Splits the text into sections separated by one or more blank lines.
+/// No section is empty.
+pub fn sections(text: &str) -> Vec<Section<'_>> {
+    let mut result = Vec::new();
+    let mut start: Option<(usize, usize)> = None;
+    let mut offset = 0;
+    for (index, line) in text.split('\n').enumerate() {
+        let blank = line.trim().is_empty();
+        match (start, blank) {
+            (None, false) => start = Some((index + 1, offset)),
+            (Some((first_line, begin)), true) => {
+                result.push(Section { first_line, text: &text[begin..offset] });
+                start = None;
+            },
+            _ => {},
+        }
+        offset += line.len() + 1;
+    }
+    if let Some((first_line, begin)) = start {
+        result.push(Section { first_line, text: &text[begin..] });
+    }
+    result
+}
+
+/// Splits the text into sections, and checks how many there are.
+pub fn sections_exactly(text: &str, count: usize) -> Result<Vec<Section<'_>>, InputError> {
+    let result = sections(text);
+    if result.len() == count {
+        Ok(result)
+    } else {
+        Err(InputError::new(0, ErrorKind::SectionCount { expected: count, found: result.len() }))
+    }
+}
+
+/// A rectangle of characters, like "#" and ".", one row per line.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Grid {
+    pub width: usize,
+    pub height: usize,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    /// The character at column x of row y, both starting at 0.
+    pub fn get(&self, x: usize, y: usize) -> u8 {
+        assert!(x < self.width && y < self.height);
+        self.cells[y * self.width + x]
+    }
+
+    /// The rows, from top to bottom.
+    pub fn rows(&self) -> impl Iterator<Item = &[u8]> {
+        self.cells.chunks(self.width)
+    }
+
+    /// The x and y of each cell holding the given character, in rows.
+    pub fn positions(&self, c: u8) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let width = self.width;
+        self.cells.iter().enumerate().filter(move |(_, &cell)| cell == c).map(move |(i, _)| (i % width, i / width))
+    }
+}
+
+/// Reads a grid from the lines, checking that every row is as wide as
+/// the first one, and that it only holds the allowed characters.
+pub fn grid<'a, I: IntoIterator<Item = Line<'a>>>(lines: I, allowed: &[u8]) -> Result<Grid, InputError> {
+    let mut width = None;
+    let mut height = 0;
+    let mut cells = Vec::new();
+    let mut last_line = 0;
+    for line in lines {
+        let row = line.text.trim_end().as_bytes();
+        let expected = *width.get_or_insert(row.len());
+        if row.len() != expected {
+            return Err(line.error(ErrorKind::RaggedRow { expected, found: row.len() }));
+        }
+        if let Some(c) = line.text.trim_end().chars().find(|&c| !c.is_ascii() || !allowed.contains(&(c as u8))) {
+            return Err(line.error(ErrorKind::UnexpectedChar(c)));
+        }
+        cells.extend_from_slice(row);
+        height += 1;
+        last_line = line.number;
+    }
+    match width {
+        Some(width) => Ok(Grid { width, height, cells }),
+        None => Err(InputError::new(last_line, ErrorKind::Missing(String::from("grid")))),
+    }
+}
+
+#[test]
+fn test_lines_and_numbers() {
+    let text = "1,2,3\n\n  4, 5 \nx\n";
+    let all: Vec<Line> = lines(text).collect();
+    assert_eq!(all.iter().map(|l| l.number).collect::<Vec<usize>>(), vec![1, 3, 4]);
+    assert_eq!(all[0].number_list::<u32>(",").unwrap(), vec![1, 2, 3]);
+    assert_eq!(all[1].number_list::<u32>(",").unwrap(), vec![4, 5]);
+    assert_eq!(all[2].parse::<u32>().unwrap_err().to_string(), "line 4: expected a number, found \"x\"");
+    assert_eq!(all[2].invalid("no good").in_file("a.txt").to_string(), "a.txt: line 4: no good");
+}
+
+#[test]
+fn test_sections() {
+    let text = "\na\nb\n\n\nc\n  \nd\ne";
+    let found = sections(text);
+    assert_eq!(
+        found,
+        vec![
+            Section { first_line: 2, text: "a\nb\n" },
+            Section { first_line: 6, text: "c\n" },
+            Section { first_line: 8, text: "d\ne" },
+        ]
+    );
+    let numbers: Vec<usize> = found[2].lines().map(|l| l.number).collect();
+    assert_eq!(numbers, vec![8, 9]);
+    assert_eq!(found[0].first().text, "a");
+    assert_eq!(found[0].rest().map(|l| l.text).collect::<Vec<&str>>(), vec!["b"]);
+    assert_eq!(
+        sections_exactly(text, 2).unwrap_err().to_string(),
+        "expected 2 sections separated by blank lines, found 3"
+    );
+}
+
+#[test]
+fn test_grid() {
+    let g = grid(lines(".#.\n..#\n###\n"), b"#.").unwrap();
+    assert_eq!((g.width, g.height), (3, 3));
+    assert_eq!(g.get(1, 0), b'#');
+    assert_eq!(g.positions(b'#').collect::<Vec<(usize, usize)>>(), vec![(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)]);
+    assert_eq!(g.rows().next(), Some(&b".#."[..]));
+    assert_eq!(grid(lines(".#.\n..\n"), b"#.").unwrap_err().to_string(), "line 2: expected a row 3 wide, found one 2 wide");
+    assert_eq!(grid(lines(".#.\n.x.\n"), b"#.").unwrap_err().to_string(), "line 2: unexpected character 'x'");
+    assert_eq!(grid(lines(""), b"#.").unwrap_err().to_string(), "missing grid");
+}
+
+#[test]
+fn test_read_file() {
+    let error = read_file("no/such/file.txt").unwrap_err();
+    assert!(error.to_string().starts_with("no/such/file.txt: "));
+    assert!(matches!(error.kind, ErrorKind::Io(_)));
+}
@@ -0,0 +1,257 @@
+//! What the solutions for all of the days have in common.
+//!
+//! Each day implements `Solution`: it parses the input text once, and
+//! then answers the two parts from the parsed input.  Reading the
+//! input file, timing, and printing are done here, the same way for
+//! every day, either by the `aoc` runner or by a day's own binary
+//! using `main`.
+
+use std::convert::TryFrom;
+use std::env;
+use std::error;
+use std::fmt;
+use std::process;
+use std::str;
+use std::time;
+
+/// The answer to one part of a puzzle.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum Answer {
+    Number(i64),
+    Text(String),
+    /// For a part that has no puzzle, like part 2 on day 25, or that
+    /// has no answer for the input.
+    None,
+}
+
+impl From<i64> for Answer {
+    fn from(n: i64) -> Answer {
+        Answer::Number(n)
+    }
+}
+
+/// Numbers too big for an i64 become text, so they aren't wrapped
+/// around to negative numbers.
+impl From<u64> for Answer {
+    fn from(n: u64) -> Answer {
+        match i64::try_from(n) {
+            Ok(n) => Answer::Number(n),
+            Err(_) => Answer::Text(n.to_string()),
+        }
+    }
+}
+
+impl From<usize> for Answer {
+    fn from(n: usize) -> Answer {
+        Answer::from(n as u64)
+    }
+}
+
+impl From<String> for Answer {
+    fn from(s: String) -> Answer {
+        Answer::Text(s)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(s: &str) -> Answer {
+        Answer::Text(String::from(s))
+    }
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(n) => write!(f, "{}", n),
+            Answer::Text(s) => write!(f, "{}", s),
+            Answer::None => write!(f, "-"),
+        }
+    }
+}
+
+/// One of the two parts of a puzzle.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const BOTH: [Part; 2] = [Part::One, Part::Two];
+
+    /// 1 or 2.
+    pub fn number(self) -> u32 {
+        match self {
+            Part::One => 1,
+            Part::Two => 2,
+        }
+    }
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl str::FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Part, String> {
+        match s {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            _ => Err(format!("there is no part {:?}", s)),
+        }
+    }
+}
+
+/// The solution to one day's puzzle.
+pub trait Solution {
+    /// The puzzle input, after parsing.
+    type Input;
+
+    /// The day in December, from 1 to 25.
+    const DAY: u32;
+
+    /// The title of the puzzle.
+    const TITLE: &'static str;
+
+    /// Parses the text of an input file.
+    fn parse(&self, text: &str) -> Result<Self::Input, Box<dyn error::Error>>;
+
+    fn part1(&self, input: &Self::Input) -> Answer;
+
+    fn part2(&self, input: &Self::Input) -> Answer;
+}
+
+/// The answer to one part, and how long it took.
+#[derive(Clone, Debug)]
+pub struct Timed {
+    pub part: Part,
+    pub answer: Answer,
+    pub elapsed: time::Duration,
+}
+
+/// What happened when a day was run on one input.
+#[derive(Clone, Debug)]
+pub struct Report {
+    pub day: u32,
+    pub title: &'static str,
+    /// How long parsing the input took.
+    pub parse_time: time::Duration,
+    /// The parts that were run, in the order they were asked for.
+    pub parts: Vec<Timed>,
+}
+
+/// A `Solution` with its input type hidden, so that the solutions for
+/// all of the days can be kept in one list.
+pub trait Puzzle {
+    fn day(&self) -> u32;
+
+    fn title(&self) -> &'static str;
+
+    /// Parses the input once, and then runs each of the parts.
+    fn run(&self, text: &str, parts: &[Part]) -> Result<Report, Box<dyn error::Error>>;
+}
+
+impl<S: Solution> Puzzle for S {
+    fn day(&self) -> u32 {
+        S::DAY
+    }
+
+    fn title(&self) -> &'static str {
+        S::TITLE
+    }
+
+    fn run(&self, text: &str, parts: &[Part]) -> Result<Report, Box<dyn error::Error>> {
+        let start = time::Instant::now();
+        let input = self.parse(text)?;
+        let parse_time = start.elapsed();
+        let mut result = Report { day: S::DAY, title: S::TITLE, parse_time, parts: Vec::new() };
+        for &part in parts {
+            let start = time::Instant::now();
+            let answer = match part {
+                Part::One => self.part1(&input),
+                Part::Two => self.part2(&input),
+            };
+            result.parts.push(Timed { part, answer, elapsed: start.elapsed() });
+        }
+        Ok(result)
+    }
+}
+
+/// The `main` for a day's own binary.  Reads the file named on the
+/// command line, or the default file if none is given, and prints the
+/// answers to both parts.
+pub fn main<S: Solution>(solution: S, default_input: &str) {
+    let file_name = env::args().nth(1).unwrap_or_else(|| String::from(default_input));
+    let text = aoc_common::read_file(&file_name).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        process::exit(1);
+    });
+    match solution.run(&text, &Part::BOTH) {
+        Ok(report) => {
+            for timed in report.parts {
+                println!("Part {}: {}", timed.part, timed.answer);
+            }
+        },
+        Err(e) => {
+            eprintln!("{}: {}", file_name, e);
+            process::exit(1);
+        },
+    }
+}
+
+#[cfg(test)]
+struct Doubling;
+
+#[cfg(test)]
+impl Solution for Doubling {
+    type Input = Vec<i64>;
+    const DAY: u32 = 1;
+    const TITLE: &'static str = "Doubling";
+
+    fn parse(&self, text: &str) -> Result<Vec<i64>, Box<dyn error::Error>> {
+        let mut result = Vec::new();
+        for word in text.split_whitespace() {
+            result.push(word.parse()?);
+        }
+        Ok(result)
+    }
+
+    fn part1(&self, input: &Vec<i64>) -> Answer {
+        Answer::from(input.iter().sum::<i64>() * 2)
+    }
+
+    fn part2(&self, input: &Vec<i64>) -> Answer {
+        Answer::from(format!("{:?}", input))
+    }
+}
+
+#[test]
+fn test_run() {
+    let puzzles: Vec<Box<dyn Puzzle>> = vec![Box::new(Doubling)];
+    let report = puzzles[0].run("1 2 3", &[Part::Two, Part::One]).unwrap();
+    assert_eq!((report.day, report.title), (1, "Doubling"));
+    let answers: Vec<(Part, Answer)> = report.parts.into_iter().map(|t| (t.part, t.answer)).collect();
+    assert_eq!(answers, vec![(Part::Two, Answer::from("[1, 2, 3]")), (Part::One, Answer::Number(12))]);
+
+    let error = puzzles[0].run("1 two", &Part::BOTH).unwrap_err();
+    assert_eq!(error.to_string(), "invalid digit found in string");
+}
+
+#[test]
+fn test_part_and_answer() {
+    assert_eq!("2".parse::<Part>(), Ok(Part::Two));
+    assert_eq!(Part::Two.number(), 2);
+    assert_eq!("3".parse::<Part>(), Err(String::from("there is no part \"3\"")));
+    assert_eq!(Answer::from(42usize).to_string(), "42");
+    assert_eq!(Answer::from(u64::MAX), Answer::Text(String::from("18446744073709551615")));
+    assert_eq!(Answer::from(i64::MAX as u64), Answer::Number(i64::MAX));
+    assert_eq!(Answer::from("abc").to_string(), "abc");
+    assert_eq!(Answer::None.to_string(), "-");
+}
@@ -0,0 +1,61 @@
+//! Compares `conway_step` with `conway_step_parallel` on a large
+//! random soup.  Run with `cargo bench -p conway_life`.
+
+use std::collections;
+use std::thread;
+use std::time;
+
+use conway_life::{neighborhood, LifeRule, Point};
+
+/// A random soup with about a third of the cells in a square alive,
+/// using a fixed seed so every run is the same.
+fn soup(size: i32) -> collections::HashSet<Point> {
+    let mut state: u64 = 12345;
+    let mut result = collections::HashSet::new();
+    for y in 0..size {
+        for x in 0..size {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            if (state >> 33).is_multiple_of(3) {
+                result.insert(conway_life::point(x, y));
+            }
+        }
+    }
+    result
+}
+
+/// Runs `steps` steps and returns the final board and the time it took.
+fn time_steps<F>(board: &collections::HashSet<Point>, steps: usize, step: F) -> (collections::HashSet<Point>, time::Duration)
+    where F: Fn(&collections::HashSet<Point>) -> collections::HashSet<Point>
+{
+    let start = time::Instant::now();
+    let mut board = board.clone();
+    for _ in 0..steps {
+        board = step(&board);
+    }
+    (board, start.elapsed())
+}
+
+fn main() {
+    let deltas: Vec<Point> = neighborhood::moore(1);
+    let rule = LifeRule::conway();
+    let board = soup(1000);
+    let steps = 5;
+    let threads = thread::available_parallelism().map(|n| n.get()).unwrap_or(4);
+    println!("{} live cells, {} steps", board.len(), steps);
+
+    let (serial, serial_time) =
+        time_steps(&board, steps, |b| conway_life::conway_step(b, &deltas, &rule));
+    println!("serial:               {:?}", serial_time);
+
+    for &n in [2, 4, threads].iter() {
+        let (parallel, parallel_time) =
+            time_steps(&board, steps, |b| conway_life::conway_step_parallel(b, &deltas, &rule, n));
+        assert_eq!(parallel, serial);
+        println!(
+            "parallel, {:2} threads: {:?} ({:.1}x)",
+            n,
+            parallel_time,
+            serial_time.as_secs_f64() / parallel_time.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,84 @@
+//! Boards: the set of live cells, stored in different ways.
+//!
+//! The `Board` trait is what the rest of the code needs from a board.
+//! A `HashSet` of cells is the sparse board that `conway_step` uses,
+//! and is best for small populations spread over a large space.
+//! `DenseBoard` stores one bit per cell in a box around the live cells,
+//! which is better when the live cells are packed together.
+
+use std::collections;
+use std::hash;
+use std::ops;
+
+use crate::rule::Rule;
+
+/// A set of live cells that can be run forward in Life.
+pub trait Board<T>: Sized {
+    /// Creates a board with no live cells.
+    fn empty() -> Self;
+
+    /// Is the cell alive?
+    fn contains(&self, cell: &T) -> bool;
+
+    /// Makes a cell alive.  Returns true if it was not alive before.
+    fn insert(&mut self, cell: T) -> bool;
+
+    /// Makes a cell dead.  Returns true if it was alive before.
+    fn remove(&mut self, cell: &T) -> bool;
+
+    /// The number of live cells.
+    fn len(&self) -> usize;
+
+    /// True if there are no live cells.
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns an iterator over the live cells, in no particular order.
+    fn cells(&self) -> Box<dyn Iterator<Item = T> + '_>;
+
+    /// Runs one step in Life, just like `conway_step`.
+    fn step<R: Rule + ?Sized>(&self, neighbor_deltas: &[T], rule: &R) -> Self;
+
+    /// Creates a board with the given cells alive.
+    fn from_cells<I: IntoIterator<Item = T>>(cells: I) -> Self {
+        let mut result = Self::empty();
+        for c in cells {
+            result.insert(c);
+        }
+        result
+    }
+}
+
+/// The sparse board: a hash set of the live cells.
+impl<T> Board<T> for collections::HashSet<T>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash
+{
+    fn empty() -> Self {
+        collections::HashSet::new()
+    }
+
+    fn contains(&self, cell: &T) -> bool {
+        collections::HashSet::contains(self, cell)
+    }
+
+    fn insert(&mut self, cell: T) -> bool {
+        collections::HashSet::insert(self, cell)
+    }
+
+    fn remove(&mut self, cell: &T) -> bool {
+        collections::HashSet::remove(self, cell)
+    }
+
+    fn len(&self) -> usize {
+        collections::HashSet::len(self)
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(self.iter().cloned())
+    }
+
+    fn step<R: Rule + ?Sized>(&self, neighbor_deltas: &[T], rule: &R) -> Self {
+        crate::conway_step(self, neighbor_deltas, rule)
+    }
+}
@@ -0,0 +1,236 @@
+//! Finding out what's on a board once it has settled down.
+//!
+//! The live cells are split into objects: groups of cells that are
+//! connected through the neighborhood.  Each object is put in a
+//! canonical form that doesn't depend on where it is, or on how it is
+//! rotated or reflected, and then looked up in a catalog of common
+//! objects.
+//!
+//! Because objects are found by connection, an object that falls
+//! apart into separate pieces in some of its phases, like a beacon
+//! or a toad, shows up as unknown pieces in those phases, so those
+//! aren't in the catalog.
+
+use std::collections;
+use std::fmt;
+use std::hash;
+use std::ops;
+
+use crate::pattern;
+use crate::point::{Planar, Point};
+
+/// Splits the live cells into groups that are connected through the
+/// neighborhood.  The groups are in no particular order.
+pub fn components<T>(
+    board: &collections::HashSet<T>,
+    neighbor_deltas: &[T]
+) -> Vec<collections::HashSet<T>>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash
+{
+    let mut unseen: collections::HashSet<T> = board.clone();
+    let mut result = Vec::new();
+    while let Some(&start) = unseen.iter().next() {
+        unseen.remove(&start);
+        let mut component = collections::HashSet::new();
+        let mut to_visit = vec![start];
+        while let Some(c) = to_visit.pop() {
+            component.insert(c);
+            for &d in neighbor_deltas.iter() {
+                if unseen.remove(&(c + d)) {
+                    to_visit.push(c + d);
+                }
+            }
+        }
+        result.push(component);
+    }
+    result
+}
+
+/// A rotation or reflection of a cell's coordinates.
+type Transform = fn(i32, i32) -> (i32, i32);
+
+/// The shape of an object, the same no matter where it is on the
+/// board and how it's rotated or reflected.
+#[derive(Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Shape(Vec<(i32, i32)>);
+
+impl Shape {
+    /// Makes the canonical shape: of the eight rotations and
+    /// reflections, moved so that the smallest x and y are zero, the
+    /// one with the smallest sorted list of cells.
+    pub fn of<'a, T: Planar + 'a, I: IntoIterator<Item = &'a T>>(cells: I) -> Shape {
+        let cells: Vec<(i32, i32)> = cells.into_iter().map(|c| (c.x(), c.y())).collect();
+        let transforms: [Transform; 8] = [
+            |x, y| (x, y),
+            |x, y| (-x, y),
+            |x, y| (x, -y),
+            |x, y| (-x, -y),
+            |x, y| (y, x),
+            |x, y| (-y, x),
+            |x, y| (y, -x),
+            |x, y| (-y, -x),
+        ];
+        transforms.iter()
+            .map(|transform| {
+                let moved: Vec<(i32, i32)> = cells.iter().map(|&(x, y)| transform(x, y)).collect();
+                let min_x = moved.iter().map(|c| c.0).min().unwrap_or(0);
+                let min_y = moved.iter().map(|c| c.1).min().unwrap_or(0);
+                let mut shape: Vec<(i32, i32)> = moved.iter().map(|&(x, y)| (x - min_x, y - min_y)).collect();
+                shape.sort();
+                shape
+            })
+            .min()
+            .map(Shape)
+            .unwrap()
+    }
+
+    /// The number of live cells.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// True if there are no live cells.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Shows the shape in plaintext `.cells` form.
+impl fmt::Display for Shape {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let cells: collections::HashSet<Point> = self.0.iter().map(|&(x, y)| Point::from_xy(x, y)).collect();
+        let text = pattern::write_cells(&pattern::Pattern { cells, name: None, rule: None });
+        write!(f, "{}", text)
+    }
+}
+
+/// Common objects in B3/S23, with one phase of each.
+const CATALOG: &[(&str, &str)] = &[
+    ("block", "OO\nOO\n"),
+    ("beehive", ".OO.\nO..O\n.OO.\n"),
+    ("loaf", ".OO.\nO..O\n.O.O\n..O.\n"),
+    ("boat", "OO.\nO.O\n.O.\n"),
+    ("ship", "OO.\nO.O\n.OO\n"),
+    ("tub", ".O.\nO.O\n.O.\n"),
+    ("pond", ".OO.\nO..O\nO..O\n.OO.\n"),
+    ("blinker", "OOO\n"),
+    ("glider", ".O.\n..O\nOOO\n"),
+    ("lightweight spaceship", ".O..O\nO....\nO...O\nOOOO.\n"),
+];
+
+/// Makes a map from the shapes of all of the phases of the objects
+/// in the catalog to their names.
+fn catalog() -> collections::HashMap<Shape, &'static str> {
+    let deltas: Vec<Point> = crate::neighborhood::moore(1);
+    let rule = crate::LifeRule::conway();
+    let mut result = collections::HashMap::new();
+    for &(name, text) in CATALOG {
+        let mut cells = pattern::read_cells::<Point>(text).unwrap().cells;
+        for _ in 0..4 {
+            result.insert(Shape::of(&cells), name);
+            cells = crate::conway_step(&cells, &deltas, &rule);
+        }
+    }
+    result
+}
+
+/// The objects on a board.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Census {
+    /// The number of each kind of known object, by name.
+    pub known: collections::BTreeMap<&'static str, usize>,
+    /// The shapes of objects that aren't in the catalog.
+    pub unknown: Vec<Shape>,
+}
+
+impl Census {
+    /// Takes a census of a B3/S23 board, using the neighborhood to
+    /// decide which cells are part of the same object.
+    pub fn of<T>(board: &collections::HashSet<T>, neighbor_deltas: &[T]) -> Census
+        where T: Planar + ops::Add<Output = T> + Eq + hash::Hash
+    {
+        let catalog = catalog();
+        let mut result = Census::default();
+        for component in components(board, neighbor_deltas) {
+            let shape = Shape::of(&component);
+            match catalog.get(&shape) {
+                Some(name) => *result.known.entry(name).or_insert(0) += 1,
+                None => result.unknown.push(shape),
+            }
+        }
+        result.unknown.sort();
+        result
+    }
+
+    /// The number of objects with the given name.
+    pub fn count(&self, name: &str) -> usize {
+        self.known.get(name).cloned().unwrap_or(0)
+    }
+}
+
+#[cfg(test)]
+use crate::point::point;
+
+#[test]
+fn test_components() {
+    let board: collections::HashSet<Point> =
+        [point(0, 0), point(1, 1), point(2, 2), point(10, 10), point(10, 11)].iter().cloned().collect();
+    let mut sizes: Vec<usize> = components(&board, &crate::neighborhood::moore(1)).iter().map(|c| c.len()).collect();
+    sizes.sort();
+    assert_eq!(sizes, vec![2, 3]);
+
+    // With von Neumann neighbors, diagonal cells aren't connected.
+    assert_eq!(components(&board, &crate::neighborhood::von_neumann(1)).len(), 4);
+}
+
+#[test]
+fn test_shape() {
+    let glider = [point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)];
+    let turned: Vec<Point> = glider.iter().map(|c| point(100 - c.y, c.x - 7)).collect();
+    let flipped: Vec<Point> = glider.iter().map(|c| point(-c.x, c.y)).collect();
+    assert_eq!(Shape::of(&glider), Shape::of(&turned));
+    assert_eq!(Shape::of(&glider), Shape::of(&flipped));
+    assert_ne!(Shape::of(&glider), Shape::of(&glider[..4]));
+    assert_eq!(Shape::of(&glider).len(), 5);
+    assert_eq!(Shape::of(&[point(5, 5), point(6, 5), point(7, 5)]).to_string(), "O\nO\nO\n");
+}
+
+#[test]
+fn test_census() {
+    let text = "\
+OO......O..
+OO......O..
+........O..
+...........
+....OOO....
+...........
+.O......OO.
+O.O.....O.O
+.O.......OO
+";
+    let board = pattern::read_cells::<Point>(text).unwrap().cells;
+    let census = Census::of(&board, &crate::neighborhood::moore(1));
+    assert_eq!(census.count("block"), 1);
+    assert_eq!(census.count("blinker"), 2);
+    assert_eq!(census.count("tub"), 1);
+    assert_eq!(census.count("ship"), 1);
+    assert_eq!(census.count("glider"), 0);
+    assert!(census.unknown.is_empty());
+}
+
+#[test]
+fn test_r_pentomino_census() {
+    // The R-pentomino settles down at generation 1103.
+    let mut board: collections::HashSet<Point> =
+        [point(1, 0), point(2, 0), point(0, 1), point(1, 1), point(1, 2)].iter().cloned().collect();
+    let deltas = crate::neighborhood::moore(1);
+    for _ in 0..1103 {
+        board = crate::conway_step(&board, &deltas, &crate::LifeRule::conway());
+    }
+    let census = Census::of(&board, &deltas);
+    let expected: collections::BTreeMap<&str, usize> =
+        [("beehive", 4), ("blinker", 4), ("block", 8), ("boat", 1), ("glider", 6), ("loaf", 1), ("ship", 1)]
+            .iter().cloned().collect();
+    assert_eq!(census.known, expected);
+    assert!(census.unknown.is_empty());
+}
@@ -0,0 +1,227 @@
+//! Detecting when a pattern settles into a repeating cycle.
+//!
+//! A `History` records a hash of each generation that doesn't change
+//! when the whole pattern is moved.  When a generation's hash matches
+//! an earlier one, the pattern has either stopped changing, started
+//! oscillating, or become a spaceship that repeats in a new place.
+//! From then on, the population at any later generation is known
+//! without running it.
+//!
+//! Generations are compared by hash and population only, so a hash
+//! collision could report a cycle that isn't there.  With 64-bit
+//! hashes that is very unlikely.
+
+use std::collections;
+use std::collections::hash_map;
+use std::hash;
+use std::hash::Hasher;
+use std::ops;
+
+use crate::rule::Rule;
+
+/// What happens to a pattern in the long run.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Fate<T> {
+    /// Every cell is dead, starting at `generation`.
+    DiesOut { generation: usize },
+    /// The pattern stops changing at `generation`.
+    StillLife { generation: usize },
+    /// Starting at `generation`, the pattern repeats in the same
+    /// place every `period` generations.
+    Oscillator { generation: usize, period: usize },
+    /// Starting at `generation`, the pattern repeats every `period`
+    /// generations, moved by `displacement`.
+    Spaceship { generation: usize, period: usize, displacement: T },
+}
+
+/// The populations of all generations seen so far, and the
+/// information needed to recognize a repeat.
+#[derive(Clone, Debug)]
+pub struct History<T> {
+    /// Maps (hash, population) to the first generation that had them.
+    seen: collections::HashMap<(u64, usize), usize>,
+    /// The population of each generation recorded.
+    populations: Vec<usize>,
+    /// The smallest cell of each generation, which is where the
+    /// normalized pattern was moved from.
+    origins: Vec<Option<T>>,
+    /// The fate, once a repeat has been seen.
+    fate: Option<Fate<T>>,
+}
+
+impl<T> History<T>
+    where T: ops::Sub<Output = T> + Copy + Eq + hash::Hash + Ord
+{
+    /// Creates an empty history.
+    pub fn new() -> History<T> {
+        History {
+            seen: collections::HashMap::new(),
+            populations: Vec::new(),
+            origins: Vec::new(),
+            fate: None,
+        }
+    }
+
+    /// Records the next generation, and returns the fate of the
+    /// pattern if this generation repeats an earlier one.  Once the
+    /// fate is known, more generations are not recorded.
+    pub fn record(&mut self, board: &collections::HashSet<T>) -> Option<Fate<T>> {
+        if self.fate.is_some() {
+            return self.fate;
+        }
+        let generation = self.populations.len();
+        let (hash, origin) = normalized_hash(board);
+        self.populations.push(board.len());
+        self.origins.push(origin);
+        if board.is_empty() {
+            self.fate = Some(Fate::DiesOut { generation });
+        } else if let Some(&first) = self.seen.get(&(hash, board.len())) {
+            let period = generation - first;
+            let displacement = origin.unwrap() - self.origins[first].unwrap();
+            self.fate = Some(
+                if self.origins[first] != origin {
+                    Fate::Spaceship { generation: first, period, displacement }
+                } else if period == 1 {
+                    Fate::StillLife { generation: first }
+                } else {
+                    Fate::Oscillator { generation: first, period }
+                }
+            );
+        } else {
+            self.seen.insert((hash, board.len()), generation);
+        }
+        self.fate
+    }
+
+    /// The fate of the pattern, if it is known yet.
+    pub fn fate(&self) -> Option<Fate<T>> {
+        self.fate
+    }
+
+    /// The number of generations recorded.
+    pub fn len(&self) -> usize {
+        self.populations.len()
+    }
+
+    /// True if no generations have been recorded.
+    pub fn is_empty(&self) -> bool {
+        self.populations.is_empty()
+    }
+
+    /// Returns the population at the given generation, either from the
+    /// generations recorded, or extrapolated from the fate.  Returns None
+    /// for generations after the last one recorded when the fate is unknown.
+    pub fn population_at(&self, generation: usize) -> Option<usize> {
+        if generation < self.populations.len() {
+            return Some(self.populations[generation]);
+        }
+        match self.fate? {
+            Fate::DiesOut { .. } => Some(0),
+            Fate::StillLife { generation: start } => Some(self.populations[start]),
+            Fate::Oscillator { generation: start, period } |
+            Fate::Spaceship { generation: start, period, .. } =>
+                Some(self.populations[start + (generation - start) % period]),
+        }
+    }
+}
+
+impl<T> Default for History<T>
+    where T: ops::Sub<Output = T> + Copy + Eq + hash::Hash + Ord
+{
+    fn default() -> History<T> {
+        History::new()
+    }
+}
+
+/// Hashes a board after moving it so its smallest cell is at the
+/// origin.  The smallest cell moves along with the pattern, so the
+/// hash is the same wherever the pattern is.  Returns the hash and
+/// the smallest cell.
+fn normalized_hash<T>(board: &collections::HashSet<T>) -> (u64, Option<T>)
+    where T: ops::Sub<Output = T> + Copy + hash::Hash + Ord
+{
+    let mut cells: Vec<T> = board.iter().cloned().collect();
+    cells.sort();
+    let origin = cells.first().cloned();
+    let mut hasher = hash_map::DefaultHasher::new();
+    if let Some(o) = origin {
+        for &c in cells.iter() {
+            (c - o).hash(&mut hasher);
+        }
+    }
+    (hasher.finish(), origin)
+}
+
+/// Runs Life from the initial board for up to `generations` steps,
+/// stopping early once the fate of the pattern is known.  Returns
+/// the last board computed and the history of the run.
+pub fn run<T, R>(
+    initial: &collections::HashSet<T>,
+    neighbor_deltas: &[T],
+    rule: &R,
+    generations: usize
+) -> (collections::HashSet<T>, History<T>)
+    where T: ops::Add<Output = T> + ops::Sub<Output = T> + Copy + Eq + hash::Hash + Ord,
+          R: Rule + ?Sized
+{
+    let mut history = History::new();
+    let mut board = initial.clone();
+    history.record(&board);
+    while history.len() <= generations && history.fate().is_none() {
+        board = crate::conway_step(&board, neighbor_deltas, rule);
+        history.record(&board);
+    }
+    (board, history)
+}
+
+#[cfg(test)]
+use crate::point::{point, Point};
+
+#[cfg(test)]
+fn run_pattern(cells: &[Point]) -> History<Point> {
+    let deltas = crate::neighborhood::moore(1);
+    let board = cells.iter().cloned().collect();
+    run(&board, &deltas, &crate::LifeRule::conway(), 1000).1
+}
+
+#[test]
+fn test_dies_out() {
+    let history = run_pattern(&[point(0, 0), point(1, 0)]);
+    assert_eq!(history.fate(), Some(Fate::DiesOut { generation: 1 }));
+    assert_eq!(history.population_at(0), Some(2));
+    assert_eq!(history.population_at(1000000), Some(0));
+}
+
+#[test]
+fn test_still_life() {
+    // Three cells in an L become a block
+    let history = run_pattern(&[point(0, 0), point(1, 0), point(0, 1)]);
+    assert_eq!(history.fate(), Some(Fate::StillLife { generation: 1 }));
+    assert_eq!(history.population_at(1000000), Some(4));
+}
+
+#[test]
+fn test_oscillator() {
+    let history = run_pattern(&[point(0, 0), point(1, 0), point(2, 0)]);
+    assert_eq!(history.fate(), Some(Fate::Oscillator { generation: 0, period: 2 }));
+    assert_eq!(history.population_at(12345), Some(3));
+}
+
+#[test]
+fn test_spaceship() {
+    let history = run_pattern(&[point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)]);
+    assert_eq!(
+        history.fate(),
+        Some(Fate::Spaceship { generation: 0, period: 4, displacement: point(1, 1) })
+    );
+    assert_eq!(history.population_at(4001), Some(5));
+}
+
+#[test]
+fn test_fate_unknown() {
+    // The R-pentomino takes 1103 generations to settle.
+    let history = run_pattern(&[point(1, 0), point(2, 0), point(0, 1), point(1, 1), point(1, 2)]);
+    assert_eq!(history.fate(), None);
+    assert_eq!(history.len(), 1001);
+    assert_eq!(history.population_at(1001), None);
+}
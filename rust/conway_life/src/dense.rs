@@ -0,0 +1,320 @@
+//! A dense board that stores one bit per cell.
+//!
+//! The bits cover a box (a rectangle, in 2-D) that holds all of the
+//! live cells.  The box grows as cells are added outside of it, and
+//! each step of Life makes a new board with a box that just fits the
+//! live cells.  This is the same approach as the `Volume` in day 17,
+//! with a bit for each cell instead of a `CubeState`.
+
+use std::fmt;
+use std::marker;
+
+use crate::board::Board;
+use crate::point::Vector;
+use crate::rule::Rule;
+
+/// A board that stores a bit for every cell in a box around the
+/// live cells.  Works for cell names with any number of dimensions.
+#[derive(Clone)]
+pub struct DenseBoard<T> {
+    /// The smallest coordinate of the box on each axis.
+    min: Vec<i32>,
+    /// The size of the box on each axis.  All zero when the box is empty.
+    size: Vec<usize>,
+    /// One bit per cell in the box, with the first axis changing fastest.
+    bits: Vec<u64>,
+    /// The number of bits set.
+    population: usize,
+    cell: marker::PhantomData<T>,
+}
+
+impl<T: Vector> DenseBoard<T> {
+    /// Creates an empty board with room for the given box of cells.
+    fn with_box(min: Vec<i32>, size: Vec<usize>) -> DenseBoard<T> {
+        let volume: usize = size.iter().product();
+        DenseBoard {
+            min,
+            size,
+            bits: vec![0; volume.div_ceil(64)],
+            population: 0,
+            cell: marker::PhantomData,
+        }
+    }
+
+    /// Creates a board holding the given cells, with a box that
+    /// just fits them.
+    fn with_box_around(cells: &[T]) -> DenseBoard<T> {
+        if cells.is_empty() {
+            return DenseBoard::with_box(vec![0; T::DIMS], vec![0; T::DIMS]);
+        }
+        let mut min = vec![i32::MAX; T::DIMS];
+        let mut max = vec![i32::MIN; T::DIMS];
+        for c in cells {
+            for axis in 0..T::DIMS {
+                min[axis] = min[axis].min(c.coord(axis));
+                max[axis] = max[axis].max(c.coord(axis));
+            }
+        }
+        let size = min.iter().zip(max.iter()).map(|(&lo, &hi)| (hi - lo) as usize + 1).collect();
+        let mut result = DenseBoard::with_box(min, size);
+        for c in cells {
+            result.insert(*c);
+        }
+        result
+    }
+
+    /// Returns the position of a cell's bit, or None if the cell is
+    /// outside the box.
+    fn index(&self, cell: &T) -> Option<usize> {
+        let mut result = 0;
+        let mut stride = 1;
+        for axis in 0..T::DIMS {
+            let offset = cell.coord(axis) as i64 - self.min[axis] as i64;
+            if offset < 0 || self.size[axis] as i64 <= offset {
+                return None;
+            }
+            result += offset as usize * stride;
+            stride *= self.size[axis];
+        }
+        Some(result)
+    }
+
+    /// Returns the cell for a bit position.
+    fn cell_at(&self, index: usize) -> T {
+        let mut rest = index;
+        let coords: Vec<i32> =
+            self.min.iter()
+                .zip(self.size.iter())
+                .map(|(&m, &s)| {
+                    let c = m + (rest % s) as i32;
+                    rest /= s;
+                    c
+                })
+                .collect();
+        T::from_coords(&coords)
+    }
+
+    /// The smallest coordinate of the box on each axis, and its size.
+    pub fn bounds(&self) -> (&[i32], &[usize]) {
+        (&self.min, &self.size)
+    }
+
+    /// Makes the box bigger so it includes the cell.  The box grows by
+    /// at least its current size, so that adding cells one at a time
+    /// doesn't copy the board over and over.
+    fn grow_to_include(&mut self, cell: &T) {
+        let cells: Vec<T> = self.cells().collect();
+        let mut min = self.min.clone();
+        let mut size = self.size.clone();
+        let has_room = size.iter().all(|&s| s != 0);
+        for axis in 0..T::DIMS {
+            let c = cell.coord(axis);
+            if !has_room {
+                min[axis] = c;
+                size[axis] = 1;
+            } else if c < min[axis] {
+                let new_min = c - self.size[axis] as i32;
+                size[axis] += (min[axis] - new_min) as usize;
+                min[axis] = new_min;
+            } else if min[axis] + size[axis] as i32 <= c {
+                size[axis] = (c - min[axis]) as usize + 1 + self.size[axis];
+            }
+        }
+        *self = DenseBoard::with_box(min, size);
+        for c in cells {
+            self.insert(c);
+        }
+    }
+}
+
+impl<T: Vector> Board<T> for DenseBoard<T> {
+    fn empty() -> Self {
+        DenseBoard::with_box(vec![0; T::DIMS], vec![0; T::DIMS])
+    }
+
+    fn contains(&self, cell: &T) -> bool {
+        match self.index(cell) {
+            Some(i) => self.bits[i / 64] & (1 << (i % 64)) != 0,
+            None => false,
+        }
+    }
+
+    fn insert(&mut self, cell: T) -> bool {
+        if self.index(&cell).is_none() {
+            self.grow_to_include(&cell);
+        }
+        let i = self.index(&cell).unwrap();
+        let mask = 1 << (i % 64);
+        let was_alive = self.bits[i / 64] & mask != 0;
+        self.bits[i / 64] |= mask;
+        if !was_alive {
+            self.population += 1;
+        }
+        !was_alive
+    }
+
+    fn remove(&mut self, cell: &T) -> bool {
+        let was_alive = self.contains(cell);
+        if was_alive {
+            let i = self.index(cell).unwrap();
+            self.bits[i / 64] &= !(1 << (i % 64));
+            self.population -= 1;
+        }
+        was_alive
+    }
+
+    fn len(&self) -> usize {
+        self.population
+    }
+
+    fn cells(&self) -> Box<dyn Iterator<Item = T> + '_> {
+        Box::new(
+            self.bits.iter()
+                .enumerate()
+                .flat_map(|(w, &word)| {
+                    (0..64).filter(move |b| word & (1 << b) != 0).map(move |b| w * 64 + b)
+                })
+                .map(move |i| self.cell_at(i))
+        )
+    }
+
+    /// Runs one step, counting neighbors in an array that covers the
+    /// box, plus room around it for cells that could be born.  Only
+    /// cells with at least one live neighbor are checked, as in
+    /// `conway_step`, so the two give the same answers.
+    fn step<R: Rule + ?Sized>(&self, neighbor_deltas: &[T], rule: &R) -> Self {
+        if self.population == 0 {
+            return DenseBoard::empty();
+        }
+
+        // How far a neighbor can be from a cell on each axis.
+        let reach: Vec<usize> =
+            (0..T::DIMS)
+                .map(|axis| {
+                    neighbor_deltas.iter().map(|d| d.coord(axis).unsigned_abs() as usize).max().unwrap_or(0)
+                })
+                .collect();
+        let work: DenseBoard<T> = DenseBoard::with_box(
+            self.min.iter().zip(reach.iter()).map(|(&m, &r)| m - r as i32).collect(),
+            self.size.iter().zip(reach.iter()).map(|(&s, &r)| s + 2 * r).collect(),
+        );
+
+        // Each neighbor is at a fixed distance from its cell in the
+        // counts array.
+        let offsets: Vec<i64> =
+            neighbor_deltas.iter()
+                .map(|d| {
+                    let mut offset = 0i64;
+                    let mut stride = 1i64;
+                    for axis in 0..T::DIMS {
+                        offset += d.coord(axis) as i64 * stride;
+                        stride *= work.size[axis] as i64;
+                    }
+                    offset
+                })
+                .collect();
+
+        let mut counts: Vec<u16> = vec![0; work.size.iter().product()];
+        for c in self.cells() {
+            let i = work.index(&c).unwrap() as i64;
+            for offset in offsets.iter() {
+                counts[(i + offset) as usize] += 1;
+            }
+        }
+
+        let alive: Vec<T> =
+            counts.iter()
+                .enumerate()
+                .filter(|(_, &n)| n != 0)
+                .map(|(i, &n)| (work.cell_at(i), n as usize))
+                .filter(|(c, n)| rule.is_alive(self.contains(c), *n))
+                .map(|(c, _)| c)
+                .collect();
+        DenseBoard::with_box_around(&alive)
+    }
+
+    fn from_cells<I: IntoIterator<Item = T>>(cells: I) -> Self {
+        let cells: Vec<T> = cells.into_iter().collect();
+        DenseBoard::with_box_around(&cells)
+    }
+}
+
+/// Two boards are equal if they have the same live cells, even if
+/// their boxes are different.
+impl<T: Vector> PartialEq for DenseBoard<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.population == other.population && self.cells().all(|c| other.contains(&c))
+    }
+}
+
+impl<T: Vector> Eq for DenseBoard<T> {}
+
+impl<T: Vector + fmt::Debug> fmt::Debug for DenseBoard<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.cells()).finish()
+    }
+}
+
+#[cfg(test)]
+use crate::point::{point, Point, PointN};
+
+#[cfg(test)]
+use std::collections;
+
+#[cfg(test)]
+use crate::neighborhood::moore;
+
+#[test]
+fn test_insert_and_remove() {
+    let mut board: DenseBoard<Point> = Board::empty();
+    assert!(board.is_empty());
+    assert!(board.insert(point(3, 4)));
+    assert!(!board.insert(point(3, 4)));
+    assert!(board.insert(point(-10, 20)));
+    assert!(board.insert(point(100, -7)));
+    assert_eq!(board.len(), 3);
+    assert!(board.contains(&point(3, 4)));
+    assert!(board.contains(&point(-10, 20)));
+    assert!(!board.contains(&point(4, 3)));
+    assert!(!board.contains(&point(1000, 1000)));
+    assert!(board.remove(&point(3, 4)));
+    assert!(!board.remove(&point(3, 4)));
+    let mut cells: Vec<Point> = board.cells().collect();
+    cells.sort();
+    assert_eq!(cells, vec![point(-10, 20), point(100, -7)]);
+}
+
+#[test]
+fn test_dense_matches_sparse() {
+    let r_pentomino = [point(1, 0), point(2, 0), point(0, 1), point(1, 1), point(1, 2)];
+    let rule = crate::LifeRule::conway();
+    let deltas = moore::<Point>(1);
+    let mut sparse: collections::HashSet<Point> = Board::from_cells(r_pentomino.iter().cloned());
+    let mut dense: DenseBoard<Point> = Board::from_cells(r_pentomino.iter().cloned());
+    for _ in 0..200 {
+        sparse = sparse.step(&deltas, &rule);
+        dense = dense.step(&deltas, &rule);
+        assert_eq!(dense, DenseBoard::from_cells(sparse.iter().cloned()));
+    }
+}
+
+#[test]
+fn test_pocket_dimension() {
+    // The sample from day 17
+    let initial = [(1, 0), (2, 1), (0, 2), (1, 2), (2, 2)];
+    let rule = crate::LifeRule::conway();
+
+    let mut board: DenseBoard<PointN<3>> =
+        Board::from_cells(initial.iter().map(|&(x, y)| PointN([x, y, 0])));
+    for _ in 0..6 {
+        board = board.step(&moore(1), &rule);
+    }
+    assert_eq!(board.len(), 112);
+
+    let mut board: DenseBoard<PointN<4>> =
+        Board::from_cells(initial.iter().map(|&(x, y)| PointN([x, y, 0, 0])));
+    for _ in 0..6 {
+        board = board.step(&moore(1), &rule);
+    }
+    assert_eq!(board.len(), 848);
+}
@@ -0,0 +1,223 @@
+//! Multi-state "Generations" automata, like Brian's Brain and Star Wars.
+//!
+//! Each cell has a state number.  State 1 is alive, and only live
+//! cells count as neighbors.  When a live cell fails to survive it
+//! does not die right away; it moves through the dying states 2, 3,
+//! and so on, one per generation, until it reaches the state count
+//! and becomes dead (state 0) again.  Dying cells can't be born.
+//!
+//! The board maps each cell to its state.  Dead cells are not stored.
+
+use std::collections;
+use std::fmt;
+use std::hash;
+use std::ops;
+use std::str;
+
+use crate::count_live_neighbors;
+use crate::rule::{parse_counts, split_rule, LifeRule, ParseRuleError, Rule};
+
+/// A birth/survival rule, plus the number of states a cell goes
+/// through, counting dead and alive.  With two states, this is an
+/// ordinary Life rule.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GenerationsRule<R = LifeRule> {
+    rule: R,
+    states: usize,
+}
+
+impl<R: Rule> GenerationsRule<R> {
+    /// Creates a rule from a birth/survival rule and a state count.
+    /// Panics if the state count is not from 2 to 256.
+    pub fn new(rule: R, states: usize) -> GenerationsRule<R> {
+        assert!((2..=256).contains(&states), "bad state count: {}", states);
+        GenerationsRule { rule, states }
+    }
+
+    /// The number of states, including dead and alive.
+    pub fn states(&self) -> usize {
+        self.states
+    }
+
+    /// The rule that decides births and survivals.
+    pub fn rule(&self) -> &R {
+        &self.rule
+    }
+
+    /// Returns the state a cell moves to, given its state now
+    /// and the number of live neighbors it has.
+    pub fn next_state(&self, state: u8, live_neighbors: usize) -> u8 {
+        let next = match state {
+            0 if self.rule.is_alive(false, live_neighbors) => 1,
+            0 => 0,
+            1 if self.rule.is_alive(true, live_neighbors) => 1,
+            _ => state as usize + 1,
+        };
+        if next < self.states { next as u8 } else { 0 }
+    }
+}
+
+/// Writes the rule in "B2/S/C3" notation.
+impl fmt::Display for GenerationsRule<LifeRule> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/C{}", self.rule, self.states)
+    }
+}
+
+/// Parses "B2/S/C3" notation, where the parts can be in any order.  The
+/// older "S/B/C" notation without letters, like "/2/3", is also accepted.
+impl str::FromStr for GenerationsRule<LifeRule> {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<GenerationsRule<LifeRule>, ParseRuleError> {
+        let mut parts = split_rule(s)?;
+        if parts.len() == 3 && parts.iter().all(|p| p.letter.is_none()) {
+            let rule = LifeRule {
+                birth: parse_counts('B', parts[1].text)?,
+                survival: parse_counts('S', parts[0].text)?,
+            };
+            return Ok(GenerationsRule { rule, states: parse_state_count(parts[2].text)? });
+        }
+        let mut state_parts = Vec::new();
+        parts.retain(|p| {
+            let is_state_part = p.letter == Some('C');
+            if is_state_part {
+                state_parts.push(p.text);
+            }
+            !is_state_part
+        });
+        match state_parts.as_slice() {
+            [] => Err(ParseRuleError::MissingPart('C')),
+            [text] => Ok(GenerationsRule {
+                rule: LifeRule::from_parts(parts)?,
+                states: parse_state_count(text)?,
+            }),
+            _ => Err(ParseRuleError::DuplicatePart('C')),
+        }
+    }
+}
+
+/// Parses the number of states in a Generations rule.
+fn parse_state_count(text: &str) -> Result<usize, ParseRuleError> {
+    match text.parse::<usize>() {
+        Ok(n) if (2..=256).contains(&n) => Ok(n),
+        _ => Err(ParseRuleError::BadStateCount(String::from(text))),
+    }
+}
+
+/// Runs one step of a Generations automaton.
+///
+/// Input is the board before, mapping each non-dead cell to its state,
+/// and output is the board after.  Only cells in state 1 count as live
+/// neighbors, just as `count_live_neighbors` counts them in `conway_step`.
+pub fn generations_step<T, R>(
+    before: &collections::HashMap<T, u8>,
+    neighbor_deltas: &[T],
+    rule: &GenerationsRule<R>
+) -> collections::HashMap<T, u8>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          R: Rule
+{
+    let live_cells = before.iter().filter(|(_, &s)| s == 1).map(|(&c, _)| c);
+    let live_neighbors = count_live_neighbors(live_cells, neighbor_deltas);
+
+    // Every cell that is not dead moves on, whether or not it has
+    // live neighbors.
+    let mut result: collections::HashMap<T, u8> =
+        before.iter()
+            .map(|(&c, &s)| (c, rule.next_state(s, live_neighbors.get(&c).cloned().unwrap_or(0))))
+            .filter(|&(_, s)| s != 0)
+            .collect();
+
+    // Dead cells can only be born if they have a live neighbor.
+    for (c, n) in live_neighbors {
+        if !before.contains_key(&c) && rule.next_state(0, n) == 1 {
+            result.insert(c, 1);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+fn x_y(x: i64, y: i64) -> i64 {
+    y * (1 << 16) + x
+}
+
+#[cfg(test)]
+fn moore_deltas() -> Vec<i64> {
+    let mut result = Vec::new();
+    for dy in -1..=1i64 {
+        for dx in -1..=1i64 {
+            if dx != 0 || dy != 0 {
+                result.push(dy * (1 << 16) + dx);
+            }
+        }
+    }
+    result
+}
+
+#[test]
+fn test_parse_generations_rule() {
+    let brians_brain = GenerationsRule::new(LifeRule::new(&[2], &[]), 3);
+    assert_eq!("B2/S/C3".parse(), Ok(brians_brain.clone()));
+    assert_eq!("c3/b2/s".parse(), Ok(brians_brain.clone()));
+    assert_eq!("/2/3".parse(), Ok(brians_brain.clone()));
+    assert_eq!(brians_brain.to_string(), "B2/S/C3");
+    assert_eq!(
+        "345/2/4".parse(),
+        Ok(GenerationsRule::new(LifeRule::new(&[2], &[3, 4, 5]), 4))
+    );
+}
+
+#[test]
+fn test_parse_generations_rule_errors() {
+    assert_eq!("B2/S".parse::<GenerationsRule>(), Err(ParseRuleError::MissingPart('C')));
+    assert_eq!("B2/S/C3/C4".parse::<GenerationsRule>(), Err(ParseRuleError::DuplicatePart('C')));
+    assert_eq!(
+        "B2/S/C1".parse::<GenerationsRule>(),
+        Err(ParseRuleError::BadStateCount(String::from("1")))
+    );
+    assert_eq!(
+        "B2/S/Cx".parse::<GenerationsRule>(),
+        Err(ParseRuleError::BadStateCount(String::from("x")))
+    );
+    assert_eq!("B2/C3".parse::<GenerationsRule>(), Err(ParseRuleError::MissingPart('S')));
+}
+
+#[test]
+fn test_next_state() {
+    let rule: GenerationsRule = "B2/S3/C4".parse().unwrap();
+    assert_eq!(rule.next_state(0, 2), 1);
+    assert_eq!(rule.next_state(0, 3), 0);
+    assert_eq!(rule.next_state(1, 3), 1);
+    assert_eq!(rule.next_state(1, 2), 2);
+    assert_eq!(rule.next_state(2, 2), 3);
+    assert_eq!(rule.next_state(3, 2), 0);
+}
+
+#[test]
+fn test_brians_brain_step() {
+    let rule: GenerationsRule = "B2/S/C3".parse().unwrap();
+    let before: collections::HashMap<i64, u8> =
+        vec![(x_y(0, 0), 1), (x_y(1, 0), 1), (x_y(5, 5), 2)].into_iter().collect();
+    let after = generations_step(&before, &moore_deltas(), &rule);
+    let expected: collections::HashMap<i64, u8> =
+        vec![
+            (x_y(0, 0), 2), (x_y(1, 0), 2),
+            (x_y(0, 1), 1), (x_y(1, 1), 1), (x_y(0, -1), 1), (x_y(1, -1), 1),
+        ].into_iter().collect();
+    assert_eq!(after, expected);
+}
+
+#[test]
+fn test_two_states_matches_conway_step() {
+    let rule: GenerationsRule = "B3/S23/C2".parse().unwrap();
+    let glider = [x_y(1, 0), x_y(2, 1), x_y(0, 2), x_y(1, 2), x_y(2, 2)];
+    let mut life: collections::HashSet<i64> = glider.iter().cloned().collect();
+    let mut generations: collections::HashMap<i64, u8> = glider.iter().map(|&c| (c, 1)).collect();
+    for _ in 0..8 {
+        life = crate::conway_step(&life, &moore_deltas(), rule.rule());
+        generations = generations_step(&generations, &moore_deltas(), &rule);
+        assert_eq!(generations.keys().cloned().collect::<collections::HashSet<_>>(), life);
+    }
+}
@@ -0,0 +1,385 @@
+//! HashLife: a memoized quadtree engine for 2-D Life-like rules.
+//!
+//! The board is stored as a quadtree of square nodes.  A node at level
+//! `k` is 2^k cells on a side, made of four level `k - 1` children.
+//! Identical nodes are stored only once, and the result of running the
+//! center of each node forward is remembered, so patterns with repeated
+//! structure (in space or in time) can be run for millions or billions
+//! of generations.
+//!
+//! Neighbors are the eight cells around a cell, as in Conway's Life.
+//! Any `Rule` can be used, except for rules where a dead cell with no
+//! live neighbors is born, because those fill all of empty space.
+
+use std::collections;
+
+use crate::point::{point, Point};
+use crate::rule::Rule;
+
+/// Index of a node in `HashLife::nodes`.
+type NodeId = usize;
+
+/// The dead leaf (a single cell).
+const DEAD: NodeId = 0;
+
+/// The live leaf (a single cell).
+const ALIVE: NodeId = 1;
+
+/// One square of the quadtree.
+#[derive(Clone, Copy, Debug)]
+struct Node {
+    /// The node is 2^level cells on a side.  Leaves are level 0.
+    level: u32,
+    /// The four quarters, in order: north-west, north-east, south-west,
+    /// south-east.  North is toward smaller y.  Unused for leaves.
+    children: [NodeId; 4],
+    /// The number of live cells in the node.
+    population: u64,
+}
+
+/// A HashLife universe for one rule.  The memoized nodes and results
+/// are kept between calls, so running related patterns, or the same
+/// pattern again, is fast.
+pub struct HashLife<'a, R: ?Sized> {
+    rule: &'a R,
+    nodes: Vec<Node>,
+    /// Finds the node with the given children.
+    index: collections::HashMap<[NodeId; 4], NodeId>,
+    /// Maps (node, j) to the center of the node after 2^j generations.
+    results: collections::HashMap<(NodeId, u32), NodeId>,
+    /// The empty node at each level.
+    empties: Vec<NodeId>,
+}
+
+impl<'a, R: Rule + ?Sized> HashLife<'a, R> {
+    /// Creates an empty universe for the rule.  Panics if the rule
+    /// makes cells with no live neighbors come alive.
+    pub fn new(rule: &'a R) -> HashLife<'a, R> {
+        assert!(!rule.is_alive(false, 0), "HashLife can't run rules with B0");
+        let leaf = |population| Node { level: 0, children: [DEAD; 4], population };
+        HashLife {
+            rule,
+            nodes: vec![leaf(0), leaf(1)],
+            index: collections::HashMap::new(),
+            results: collections::HashMap::new(),
+            empties: vec![DEAD],
+        }
+    }
+
+    /// Runs the board forward by `generations` steps.
+    pub fn advance(
+        &mut self,
+        before: &collections::HashSet<Point>,
+        generations: u64
+    ) -> collections::HashSet<Point> {
+        let mut root = self.build(before);
+        for k in 0..64 {
+            if generations & (1 << k) != 0 {
+                root = self.advance_root(root, k);
+            }
+        }
+        self.extract(root)
+    }
+
+    /// Runs the board forward by 2^k steps.
+    pub fn advance_pow2(
+        &mut self,
+        before: &collections::HashSet<Point>,
+        k: u32
+    ) -> collections::HashSet<Point> {
+        let root = self.build(before);
+        let root = self.advance_root(root, k);
+        self.extract(root)
+    }
+
+    /// The number of distinct nodes in the universe so far.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Runs a root node forward by 2^k steps.  A root node at level L
+    /// covers the cells from -2^(L-1) up to 2^(L-1) on both axes, and
+    /// the result is another root node.
+    fn advance_root(&mut self, root: NodeId, k: u32) -> NodeId {
+        // Make the root big enough that the pattern can't grow past
+        // its edges in 2^k generations, with room to spare so the result
+        // (the center half of the root) contains all of it.
+        let mut root = root;
+        while self.nodes[root].level < k + 2 || !self.is_centered(root) {
+            root = self.expand(root);
+        }
+        let root = self.expand(root);
+        self.successor(root, k)
+    }
+
+    /// Builds the root node for a board.
+    fn build(&mut self, board: &collections::HashSet<Point>) -> NodeId {
+        let mut level = 1;
+        let fits = |level: u32, p: &Point| {
+            let half = 1i64 << (level - 1);
+            (-half..half).contains(&(p.x as i64)) && (-half..half).contains(&(p.y as i64))
+        };
+        while !board.iter().all(|p| fits(level, p)) {
+            level += 1;
+        }
+        let half = 1i64 << (level - 1);
+        let cells: Vec<(i64, i64)> = board.iter().map(|p| (p.x as i64 + half, p.y as i64 + half)).collect();
+        self.build_node(cells, level)
+    }
+
+    /// Builds a node from cells given relative to its north-west corner.
+    fn build_node(&mut self, cells: Vec<(i64, i64)>, level: u32) -> NodeId {
+        if cells.is_empty() {
+            return self.empty(level);
+        }
+        if level == 0 {
+            return ALIVE;
+        }
+        let half = 1i64 << (level - 1);
+        let mut quarters: [Vec<(i64, i64)>; 4] = [Vec::new(), Vec::new(), Vec::new(), Vec::new()];
+        for (x, y) in cells {
+            let i = ((y >= half) as usize) * 2 + (x >= half) as usize;
+            quarters[i].push((x % half, y % half));
+        }
+        let [nw, ne, sw, se] = quarters;
+        let nw = self.build_node(nw, level - 1);
+        let ne = self.build_node(ne, level - 1);
+        let sw = self.build_node(sw, level - 1);
+        let se = self.build_node(se, level - 1);
+        self.join(nw, ne, sw, se)
+    }
+
+    /// Lists the live cells in a root node.
+    fn extract(&self, root: NodeId) -> collections::HashSet<Point> {
+        let mut result = collections::HashSet::new();
+        let half = 1i64 << (self.nodes[root].level - 1);
+        self.extract_node(root, -half, -half, &mut result);
+        result
+    }
+
+    fn extract_node(&self, node: NodeId, x: i64, y: i64, result: &mut collections::HashSet<Point>) {
+        let n = &self.nodes[node];
+        if n.population == 0 {
+            return;
+        }
+        if n.level == 0 {
+            let coord = |c: i64| {
+                use std::convert::TryFrom;
+                i32::try_from(c).expect("cell moved past the range of i32")
+            };
+            result.insert(point(coord(x), coord(y)));
+            return;
+        }
+        let half = 1i64 << (n.level - 1);
+        for (i, &child) in n.children.iter().enumerate() {
+            let dx = (i % 2) as i64 * half;
+            let dy = (i / 2) as i64 * half;
+            self.extract_node(child, x + dx, y + dy, result);
+        }
+    }
+
+    /// Returns the node with the given children, making it if needed.
+    fn join(&mut self, nw: NodeId, ne: NodeId, sw: NodeId, se: NodeId) -> NodeId {
+        let children = [nw, ne, sw, se];
+        if let Some(&id) = self.index.get(&children) {
+            return id;
+        }
+        let node = Node {
+            level: self.nodes[nw].level + 1,
+            children,
+            population: children.iter().map(|&c| self.nodes[c].population).sum(),
+        };
+        let id = self.nodes.len();
+        self.nodes.push(node);
+        self.index.insert(children, id);
+        id
+    }
+
+    /// Returns the empty node at the given level.
+    fn empty(&mut self, level: u32) -> NodeId {
+        while self.empties.len() <= level as usize {
+            let e = *self.empties.last().unwrap();
+            let bigger = self.join(e, e, e, e);
+            self.empties.push(bigger);
+        }
+        self.empties[level as usize]
+    }
+
+    /// Returns one child of a node: 0 = nw, 1 = ne, 2 = sw, 3 = se.
+    fn child(&self, node: NodeId, i: usize) -> NodeId {
+        self.nodes[node].children[i]
+    }
+
+    /// Returns the node one level down that is centered in this one.
+    fn center(&mut self, node: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.nodes[node].children;
+        let (a, b, c, d) = (self.child(nw, 3), self.child(ne, 2), self.child(sw, 1), self.child(se, 0));
+        self.join(a, b, c, d)
+    }
+
+    /// True if all of the live cells are in the center of the node.
+    fn is_centered(&mut self, node: NodeId) -> bool {
+        let center = self.center(node);
+        self.nodes[center].population == self.nodes[node].population
+    }
+
+    /// Returns a node one level up, with this one in its center.
+    fn expand(&mut self, node: NodeId) -> NodeId {
+        let [nw, ne, sw, se] = self.nodes[node].children;
+        let e = self.empty(self.nodes[node].level - 1);
+        let new_nw = self.join(e, e, e, nw);
+        let new_ne = self.join(e, e, ne, e);
+        let new_sw = self.join(e, sw, e, e);
+        let new_se = self.join(se, e, e, e);
+        self.join(new_nw, new_ne, new_sw, new_se)
+    }
+
+    /// Runs a level 2 node (4x4) for one generation, and returns
+    /// the center (2x2) as a level 1 node.
+    fn base_step(&mut self, node: NodeId) -> NodeId {
+        let mut grid = [[false; 4]; 4];
+        for (row, cells) in grid.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                let quarter = self.child(node, (row / 2) * 2 + col / 2);
+                *cell = self.child(quarter, (row % 2) * 2 + col % 2) == ALIVE;
+            }
+        }
+        let mut next = [DEAD; 4];
+        for (i, cell) in next.iter_mut().enumerate() {
+            let (row, col) = (1 + i / 2, 1 + i % 2);
+            let live_neighbors =
+                ((row - 1)..=(row + 1))
+                    .flat_map(|r| ((col - 1)..=(col + 1)).map(move |c| (r, c)))
+                    .filter(|&(r, c)| (r, c) != (row, col) && grid[r][c])
+                    .count();
+            if self.rule.is_alive(grid[row][col], live_neighbors) {
+                *cell = ALIVE;
+            }
+        }
+        self.join(next[0], next[1], next[2], next[3])
+    }
+
+    /// Returns the center of a level k node, run forward 2^j generations,
+    /// where j is at most k - 2.
+    fn successor(&mut self, node: NodeId, j: u32) -> NodeId {
+        let level = self.nodes[node].level;
+        debug_assert!(2 <= level && j <= level - 2);
+        if self.nodes[node].population == 0 {
+            return self.empty(level - 1);
+        }
+        if let Some(&result) = self.results.get(&(node, j)) {
+            return result;
+        }
+        let result = if level == 2 {
+            self.base_step(node)
+        } else {
+            let full_speed = j == level - 2;
+
+            // The 4x4 grid of grandchildren
+            let mut g = [[DEAD; 4]; 4];
+            for (row, cells) in g.iter_mut().enumerate() {
+                for (col, cell) in cells.iter_mut().enumerate() {
+                    let quarter = self.child(node, (row / 2) * 2 + col / 2);
+                    *cell = self.child(quarter, (row % 2) * 2 + col % 2);
+                }
+            }
+
+            // Nine overlapping nodes one level down, each either run
+            // forward half way, or just centered.
+            let mut r = [[DEAD; 3]; 3];
+            for row in 0..3 {
+                for col in 0..3 {
+                    let sub = self.join(g[row][col], g[row][col + 1], g[row + 1][col], g[row + 1][col + 1]);
+                    r[row][col] = if full_speed { self.successor(sub, j - 1) } else { self.center(sub) };
+                }
+            }
+
+            // Four overlapping nodes made from those, run forward the
+            // rest of the way.
+            let mut q = [DEAD; 4];
+            for (i, quarter) in q.iter_mut().enumerate() {
+                let (row, col) = (i / 2, i % 2);
+                let sub = self.join(r[row][col], r[row][col + 1], r[row + 1][col], r[row + 1][col + 1]);
+                *quarter = self.successor(sub, if full_speed { j - 1 } else { j });
+            }
+            self.join(q[0], q[1], q[2], q[3])
+        };
+        self.results.insert((node, j), result);
+        result
+    }
+}
+
+/// Runs a 2-D board forward by `generations` steps with HashLife.
+///
+/// The result is the same as calling `conway_step` that many times with
+/// the eight neighbors around each cell.
+pub fn advance<R: Rule + ?Sized>(
+    before: &collections::HashSet<Point>,
+    rule: &R,
+    generations: u64
+) -> collections::HashSet<Point> {
+    HashLife::new(rule).advance(before, generations)
+}
+
+#[cfg(test)]
+fn step_simply<R: Rule>(
+    before: &collections::HashSet<Point>,
+    rule: &R,
+    generations: u64
+) -> collections::HashSet<Point> {
+    let mut deltas = Vec::new();
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            if dx != 0 || dy != 0 {
+                deltas.push(point(dx, dy));
+            }
+        }
+    }
+    let mut board = before.clone();
+    for _ in 0..generations {
+        board = crate::conway_step(&board, &deltas, rule);
+    }
+    board
+}
+
+#[cfg(test)]
+fn r_pentomino() -> collections::HashSet<Point> {
+    [point(1, 0), point(2, 0), point(0, 1), point(1, 1), point(1, 2)].iter().cloned().collect()
+}
+
+#[test]
+fn test_matches_conway_step() {
+    let rule = crate::LifeRule::conway();
+    for &generations in [0, 1, 2, 5, 64, 100].iter() {
+        assert_eq!(
+            advance(&r_pentomino(), &rule, generations),
+            step_simply(&r_pentomino(), &rule, generations)
+        );
+    }
+}
+
+#[test]
+fn test_matches_conway_step_other_rule() {
+    let high_life: crate::LifeRule = "B36/S23".parse().unwrap();
+    let start: collections::HashSet<Point> =
+        [point(-3, 7), point(-2, 7), point(-1, 7), point(0, 8), point(5, -4), point(5, -5), point(6, -5)]
+            .iter().cloned().collect();
+    let mut universe = HashLife::new(&high_life);
+    assert_eq!(universe.advance(&start, 37), step_simply(&start, &high_life, 37));
+    assert_eq!(universe.advance_pow2(&start, 5), step_simply(&start, &high_life, 32));
+}
+
+#[test]
+fn test_long_run() {
+    // The R-pentomino settles by generation 1103, leaving 116 cells,
+    // counting the six gliders flying away.
+    let rule = crate::LifeRule::conway();
+    let after = advance(&r_pentomino(), &rule, 1 << 20);
+    assert_eq!(after.len(), 116);
+
+    // A glider moves one cell diagonally every four generations
+    let glider: collections::HashSet<Point> =
+        [point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)].iter().cloned().collect();
+    let moved: collections::HashSet<Point> = glider.iter().map(|&p| p + point(1 << 18, 1 << 18)).collect();
+    assert_eq!(HashLife::new(&rule).advance_pow2(&glider, 20), moved);
+}
@@ -0,0 +1,223 @@
+//! Stepping a board by looking only at what changed.
+//!
+//! `conway_step` counts the neighbors of every live cell on every
+//! step.  An `Incremental` board keeps the neighbor counts from one
+//! step to the next, and updates them only for the cells that were
+//! born or died.  A cell can only change if it, or one of its
+//! neighbors, changed in the step before, so those are the only
+//! cells checked.  Parts of the board that have settled down cost
+//! nothing.
+//!
+//! Each step returns a `Diff` with the cells that were born and the
+//! cells that died, which is what a display needs to update itself.
+
+use std::collections;
+use std::hash;
+use std::ops;
+
+use crate::rule::Rule;
+use crate::topology::{self, Topology};
+
+/// The changes made by one step.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Diff<T: Eq + hash::Hash> {
+    /// Cells that were dead and are now alive.
+    pub births: collections::HashSet<T>,
+    /// Cells that were alive and are now dead.
+    pub deaths: collections::HashSet<T>,
+}
+
+impl<T: Copy + Eq + hash::Hash> Diff<T> {
+    /// True if nothing changed.
+    pub fn is_empty(&self) -> bool {
+        self.births.is_empty() && self.deaths.is_empty()
+    }
+
+    /// Makes the same changes to another copy of the board.
+    pub fn apply(&self, board: &mut collections::HashSet<T>) {
+        for c in self.deaths.iter() {
+            board.remove(c);
+        }
+        board.extend(self.births.iter().cloned());
+    }
+}
+
+/// A board that remembers its neighbor counts, and which cells need
+/// to be checked on the next step.
+#[derive(Clone, Debug)]
+pub struct Incremental<T, P = topology::Unbounded> {
+    cells: collections::HashSet<T>,
+    /// The number of live neighbors of each cell, for cells with at
+    /// least one.
+    counts: collections::HashMap<T, usize>,
+    /// The cells that could change on the next step.
+    pending: collections::HashSet<T>,
+    neighbor_deltas: Vec<T>,
+    topology: P,
+}
+
+impl<T> Incremental<T, topology::Unbounded>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash
+{
+    /// Creates a board on an unbounded grid.  The neighbor deltas
+    /// are fixed for the life of the board.
+    pub fn new(cells: collections::HashSet<T>, neighbor_deltas: &[T]) -> Self {
+        Incremental::with_topology(cells, neighbor_deltas, topology::Unbounded)
+    }
+}
+
+impl<T, P> Incremental<T, P>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          P: Topology<T>
+{
+    /// Creates a board with the given topology.  All of the cells
+    /// must already be on the board.
+    pub fn with_topology(cells: collections::HashSet<T>, neighbor_deltas: &[T], topology: P) -> Self {
+        let mut result = Incremental {
+            cells: collections::HashSet::new(),
+            counts: collections::HashMap::new(),
+            pending: collections::HashSet::new(),
+            neighbor_deltas: neighbor_deltas.to_vec(),
+            topology,
+        };
+        for &c in cells.iter() {
+            result.update_counts(c, true);
+        }
+        result.pending = result.counts.keys().cloned().chain(cells.iter().cloned()).collect();
+        result.cells = cells;
+        result
+    }
+
+    /// The live cells.
+    pub fn cells(&self) -> &collections::HashSet<T> {
+        &self.cells
+    }
+
+    /// The number of cells the next step will check.
+    pub fn pending(&self) -> usize {
+        self.pending.len()
+    }
+
+    /// Adds one to the counts of the cell's neighbors when it's born,
+    /// or subtracts one when it dies.  Returns the neighbors.
+    fn update_counts(&mut self, cell: T, born: bool) -> Vec<T> {
+        let neighbors: Vec<T> =
+            self.neighbor_deltas.iter()
+                .filter_map(|&d| self.topology.normalize(cell + d))
+                .collect();
+        for &n in neighbors.iter() {
+            if born {
+                *self.counts.entry(n).or_insert(0) += 1;
+            } else {
+                let count = self.counts.get_mut(&n).unwrap();
+                *count -= 1;
+                if *count == 0 {
+                    self.counts.remove(&n);
+                }
+            }
+        }
+        neighbors
+    }
+
+    /// Runs one step in Life, giving the same board as `conway_step_in`,
+    /// and returns what changed.
+    pub fn step<R: Rule + ?Sized>(&mut self, rule: &R) -> Diff<T> {
+        let mut diff = Diff { births: collections::HashSet::new(), deaths: collections::HashSet::new() };
+        for c in self.pending.drain() {
+            let count = self.counts.get(&c).cloned().unwrap_or(0);
+            let was_alive = self.cells.contains(&c);
+            let is_alive = count != 0 && rule.is_alive(was_alive, count);
+            if is_alive && !was_alive {
+                diff.births.insert(c);
+            } else if was_alive && !is_alive {
+                diff.deaths.insert(c);
+            }
+        }
+
+        // All of the cells were checked using the old counts, so now
+        // the counts can be updated.
+        let changes: Vec<(T, bool)> =
+            diff.births.iter().map(|&c| (c, true))
+                .chain(diff.deaths.iter().map(|&c| (c, false)))
+                .collect();
+        for (c, born) in changes {
+            if born {
+                self.cells.insert(c);
+            } else {
+                self.cells.remove(&c);
+            }
+            let neighbors = self.update_counts(c, born);
+            self.pending.insert(c);
+            self.pending.extend(neighbors);
+        }
+        diff
+    }
+}
+
+#[cfg(test)]
+use crate::point::{point, Point};
+
+#[cfg(test)]
+use crate::{neighborhood, LifeRule};
+
+#[test]
+fn test_matches_conway_step() {
+    let r_pentomino: collections::HashSet<Point> =
+        [point(1, 0), point(2, 0), point(0, 1), point(1, 1), point(1, 2)].iter().cloned().collect();
+    let deltas = neighborhood::moore(1);
+    let rule = LifeRule::conway();
+    let mut board = Incremental::new(r_pentomino.clone(), &deltas);
+    let mut expected = r_pentomino.clone();
+    let mut copy = r_pentomino;
+    for _ in 0..200 {
+        let diff = board.step(&rule);
+        expected = crate::conway_step(&expected, &deltas, &rule);
+        diff.apply(&mut copy);
+        assert_eq!(board.cells(), &expected);
+        assert_eq!(copy, expected);
+    }
+}
+
+#[test]
+fn test_only_changes_are_checked() {
+    // A block, which never changes, and a blinker far away
+    let cells: collections::HashSet<Point> =
+        [point(0, 0), point(1, 0), point(0, 1), point(1, 1), point(100, 0), point(101, 0), point(102, 0)]
+            .iter().cloned().collect();
+    let mut board = Incremental::new(cells, &neighborhood::moore(1));
+    let rule = LifeRule::conway();
+    let diff = board.step(&rule);
+    assert_eq!(diff.births, [point(101, -1), point(101, 1)].iter().cloned().collect());
+    assert_eq!(diff.deaths, [point(100, 0), point(102, 0)].iter().cloned().collect());
+
+    // Only the cells around the four that changed are checked.
+    assert!(board.pending() <= 4 * 9);
+    for _ in 0..10 {
+        assert!(!board.step(&rule).is_empty());
+        assert!(board.pending() <= 4 * 9);
+    }
+    assert_eq!(board.cells().len(), 7);
+
+    // A still life has nothing left to check after one step.
+    let block: collections::HashSet<Point> =
+        [point(0, 0), point(1, 0), point(0, 1), point(1, 1)].iter().cloned().collect();
+    let mut board = Incremental::new(block, &neighborhood::moore(1));
+    assert!(board.step(&rule).is_empty());
+    assert_eq!(board.pending(), 0);
+}
+
+#[test]
+fn test_torus() {
+    let torus = topology::Torus(topology::Rectangle::new(0, 0, 8, 8));
+    let glider: collections::HashSet<Point> =
+        [point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)].iter().cloned().collect();
+    let deltas = neighborhood::moore(1);
+    let rule = LifeRule::conway();
+    let mut board = Incremental::with_topology(glider.clone(), &deltas, torus);
+    let mut expected = glider;
+    for _ in 0..40 {
+        board.step(&rule);
+        expected = crate::conway_step_in(&expected, &deltas, &rule, &torus);
+        assert_eq!(board.cells(), &expected);
+    }
+}
@@ -1,3 +1,13 @@
+//! Generalized implementation of Conway's Game of Life.
+//!
+//! The board is a set of cells that are active, named by
+//! a type that supports Clone + Eq + Hash, so it can be
+//! stored in a hash table.
+//!
+//! Cell names must also support Add.  Neighbors of a cell
+//! are computed by adding one of a fixed set of offsets
+//! to the cell.
+
 #[cfg(test)]
 mod tests {
     #[test]
@@ -6,60 +16,178 @@ mod tests {
     }
 }
 
-/// Generalized implementation of Conway's Game of Life.
-/// 
-/// The board is a set of cells that are active, named by
-/// a type that supports Clone + Eq + Hash, so it can be
-/// stored in a hash table.
-/// 
-/// Cell names must also support Add.  Neighbors of a cell
-/// are computed by adding one of a fixed set of offsets
-/// to the cell.
+mod board;
+pub mod census;
+pub mod cycle;
+mod dense;
+mod generations;
+pub mod hashlife;
+pub mod incremental;
+pub mod neighborhood;
+mod parallel;
+pub mod pattern;
+mod point;
+pub mod predecessor;
+pub mod render;
+mod rule;
+pub mod simulation;
+pub mod topology;
+
+pub use board::Board;
+pub use dense::DenseBoard;
+pub use generations::{generations_step, GenerationsRule};
+pub use parallel::{conway_step_in_parallel, conway_step_parallel};
+pub use point::{point, Planar, Point, PointN, Vector};
+pub use rule::{LifeRule, ParseRuleError, Rule};
+pub use topology::Topology;
 
 use std::collections;
 use std::hash;
 use std::ops;
 
 /// Counts the number of neighbors of each cell that are alive.
-/// 
+///
 /// Returns a map from cell to live neigbor count.  Only cells
 /// with non-zero counts are included in the set.
-fn count_live_neighbors<T: ops::Add<Output = T> + Copy + Eq + hash::Hash>(
-    before: &collections::HashSet<T>,
+fn count_live_neighbors<T, I>(
+    live_cells: I,
     neighbor_deltas: &[T]
-) -> collections::HashMap<T, usize> 
+) -> collections::HashMap<T, usize>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          I: IntoIterator<Item = T>
+{
+    count_live_neighbors_in(live_cells, neighbor_deltas, &topology::Unbounded)
+}
+
+/// Counts live neighbors like `count_live_neighbors`, with each
+/// neighbor passed through the topology first.  Neighbors that are
+/// off the board are not counted.
+fn count_live_neighbors_in<T, I, P>(
+    live_cells: I,
+    neighbor_deltas: &[T],
+    topology: &P
+) -> collections::HashMap<T, usize>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          I: IntoIterator<Item = T>,
+          P: Topology<T> + ?Sized
 {
     let mut result: collections::HashMap<T, usize> = collections::HashMap::new();
-    for &c in before.iter() {
+    for c in live_cells {
         for &d in neighbor_deltas.iter() {
-            let neighbor = c + d;
-            let old_count = result.get(&neighbor).map(|&n| n).unwrap_or(0);
-            result.insert(neighbor, old_count + 1);
+            if let Some(neighbor) = topology.normalize(c + d) {
+                *result.entry(neighbor).or_insert(0) += 1;
+            }
         }
     }
     result
 }
 
 /// Runs one step in Life.
-/// 
+///
 /// Input is the board before, and output is the board after.
-pub fn conway_step<T: ops::Add<Output = T> + Copy + Eq + hash::Hash>(
+/// The rule decides which cells are alive after the step; it can
+/// be a `LifeRule` parsed from a string like "B3/S23", or any
+/// closure taking `(was_alive, live_neighbor_count)`.
+pub fn conway_step<T, R>(
     before: &collections::HashSet<T>,
     neighbor_deltas: &[T],
-    is_alive: fn(bool, usize) -> bool
-) -> collections::HashSet<T> 
+    rule: &R
+) -> collections::HashSet<T>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          R: Rule + ?Sized
+{
+    conway_step_in(before, neighbor_deltas, rule, &topology::Unbounded)
+}
+
+/// Runs one step in Life on a board with the given topology.
+///
+/// All of the cells in `before` must already be on the board.
+pub fn conway_step_in<T, R, P>(
+    before: &collections::HashSet<T>,
+    neighbor_deltas: &[T],
+    rule: &R,
+    topology: &P
+) -> collections::HashSet<T>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          R: Rule + ?Sized,
+          P: Topology<T> + ?Sized
 {
     // Count the number of neighbors of each cell that are alive.
-    let live_neigbors = count_live_neighbors(before, neighbor_deltas);
-    
-    // Use the aliveness test to decide which cells are alive in 
-    // the result.  Any cell that is live in the output must have
-    // at least one live neighbor, so it must appear in live_neighbors.
-    live_neigbors.iter()
-        .filter(|(c, &n)| {
+    let live_neigbors = count_live_neighbors_in(before.iter().cloned(), neighbor_deltas, topology);
+    alive_after(before, &live_neigbors, rule).collect()
+}
+
+/// Uses the aliveness test to decide which cells are alive after a
+/// step.  Any cell that is live in the output must have at least one
+/// live neighbor, so it must appear in live_neighbors.
+fn alive_after<'a, T, R>(
+    before: &'a collections::HashSet<T>,
+    live_neighbors: &'a collections::HashMap<T, usize>,
+    rule: &'a R
+) -> impl Iterator<Item = T> + 'a
+    where T: Copy + Eq + hash::Hash,
+          R: Rule + ?Sized
+{
+    live_neighbors.iter()
+        .filter(move |(c, &n)| {
             let was_alive = before.contains(c);
-            is_alive(was_alive, n)
+            rule.is_alive(was_alive, n)
         })
-        .map(|(c, _)| c.clone())
-        .collect() 
-}
\ No newline at end of file
+        .map(|(c, _)| *c)
+}
+
+#[test]
+fn test_conway_step_with_parsed_rule() {
+    // Pack x and y into one number, since tuples don't support Add.
+    let mut deltas: Vec<i64> = Vec::new();
+    for dx in -1..=1 {
+        for dy in -1..=1 {
+            if dx != 0 || dy != 0 {
+                deltas.push(dy * 1000 + dx);
+            }
+        }
+    }
+    let horizontal: collections::HashSet<i64> = [-1, 0, 1].iter().cloned().collect();
+    let vertical: collections::HashSet<i64> = [-1000, 0, 1000].iter().cloned().collect();
+    let rule: LifeRule = "B3/S23".parse().unwrap();
+    assert_eq!(conway_step(&horizontal, &deltas, &rule), vertical);
+    assert_eq!(conway_step(&vertical, &deltas, &rule), horizontal);
+}
+
+#[test]
+fn test_conway_step_with_closure() {
+    // A one-dimensional rule that captures its birth threshold.  Cells
+    // with no live neighbors are never examined, so the start cell dies.
+    let threshold = 1;
+    let rule = move |was_alive: bool, n: usize| was_alive || n >= threshold;
+    let mut board: collections::HashSet<i64> = [0].iter().cloned().collect();
+    for _ in 0..3 {
+        board = conway_step(&board, &[-1, 1], &rule);
+    }
+    assert_eq!(board, [-3, -1, 1, 3].iter().cloned().collect());
+}
+
+#[test]
+fn test_conway_step_in_bounded() {
+    // A vertical blinker against the left wall loses the cell that
+    // would be born past the wall.
+    let bounded = topology::Bounded(topology::Rectangle::new(0, 0, 5, 5));
+    let vertical: collections::HashSet<Point> = [point(0, 1), point(0, 2), point(0, 3)].iter().cloned().collect();
+    let after = conway_step_in(&vertical, &neighborhood::moore(1), &LifeRule::conway(), &bounded);
+    assert_eq!(after, [point(0, 2), point(1, 2)].iter().cloned().collect());
+}
+
+#[test]
+fn test_conway_step_in_torus() {
+    // A glider moves one cell diagonally every four generations, so
+    // after 20 generations on a 5x5 torus it is back where it started.
+    let torus = topology::Torus(topology::Rectangle::new(0, 0, 5, 5));
+    let glider: collections::HashSet<Point> =
+        [point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)].iter().cloned().collect();
+    let mut board = glider.clone();
+    for generation in 1..=20 {
+        board = conway_step_in(&board, &neighborhood::moore(1), &LifeRule::conway(), &torus);
+        assert_eq!(board.len(), 5);
+        assert_eq!(board == glider, generation == 20);
+    }
+}
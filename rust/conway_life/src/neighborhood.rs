@@ -0,0 +1,99 @@
+//! Ready-made lists of neighbor deltas.
+//!
+//! Each function returns the offsets from a cell to its neighbors,
+//! for any cell name that implements `Vector`, ready to pass to
+//! `conway_step`.  The cell itself (all zeros) is never included.
+
+use crate::point::Vector;
+
+/// All of the coordinate lists with each coordinate in -radius..=radius,
+/// for a cell name with `dims` coordinates.
+fn cube(dims: usize, radius: i32) -> Vec<Vec<i32>> {
+    let mut result: Vec<Vec<i32>> = vec![vec![]];
+    for _ in 0..dims {
+        result = result.into_iter()
+            .flat_map(|v| {
+                (-radius..=radius).map(move |c| {
+                    let mut longer = v.clone();
+                    longer.push(c);
+                    longer
+                })
+            })
+            .collect();
+    }
+    result
+}
+
+/// Makes the deltas that pass the filter, leaving out the cell itself.
+fn deltas_where<T: Vector, F: Fn(&[i32]) -> bool>(radius: u32, keep: F) -> Vec<T> {
+    cube(T::DIMS, radius as i32)
+        .into_iter()
+        .filter(|v| v.iter().any(|&c| c != 0) && keep(v))
+        .map(|v| T::from_coords(&v))
+        .collect()
+}
+
+/// The Moore neighborhood: every cell within `radius` steps on each
+/// axis.  With radius 1, that's 8 neighbors in 2-D, 26 in 3-D, and 80
+/// in 4-D, as in day 17.
+pub fn moore<T: Vector>(radius: u32) -> Vec<T> {
+    deltas_where(radius, |_| true)
+}
+
+/// The von Neumann neighborhood: every cell within `radius` steps,
+/// moving along one axis at a time.  With radius 1, that's the 4
+/// orthogonal neighbors in 2-D.
+pub fn von_neumann<T: Vector>(radius: u32) -> Vec<T> {
+    deltas_where(radius, |v| v.iter().map(|c| c.unsigned_abs()).sum::<u32>() <= radius)
+}
+
+/// The neighbors on a hex grid with axial coordinates, within
+/// `radius` steps.  The six neighbors at radius 1 are:
+///
+/// ```text
+///    nw (-1, 1)   ne (0, 1)
+/// w (-1, 0)           e (1, 0)
+///    sw (0, -1)   se (1, -1)
+/// ```
+///
+/// Panics if the cell name doesn't have exactly two coordinates.
+pub fn hexagonal<T: Vector>(radius: u32) -> Vec<T> {
+    assert_eq!(T::DIMS, 2, "hex grids have two coordinates");
+    deltas_where(radius, |v| (v[0] + v[1]).unsigned_abs() <= radius)
+}
+
+#[cfg(test)]
+use crate::point::{point, Point, PointN};
+
+#[test]
+fn test_moore() {
+    let deltas: Vec<Point> = moore(1);
+    assert_eq!(
+        deltas,
+        vec![
+            point(-1, -1), point(-1, 0), point(-1, 1), point(0, -1),
+            point(0, 1), point(1, -1), point(1, 0), point(1, 1),
+        ]
+    );
+    assert_eq!(moore::<Point>(2).len(), 24);
+    assert_eq!(moore::<PointN<3>>(1).len(), 26);
+    assert_eq!(moore::<PointN<4>>(1).len(), 80);
+}
+
+#[test]
+fn test_von_neumann() {
+    let deltas: Vec<Point> = von_neumann(1);
+    assert_eq!(deltas, vec![point(-1, 0), point(0, -1), point(0, 1), point(1, 0)]);
+    assert_eq!(von_neumann::<Point>(2).len(), 12);
+    assert_eq!(von_neumann::<PointN<3>>(1).len(), 6);
+}
+
+#[test]
+fn test_hexagonal() {
+    let deltas: Vec<Point> = hexagonal(1);
+    assert_eq!(
+        deltas,
+        vec![point(-1, 0), point(-1, 1), point(0, -1), point(0, 1), point(1, -1), point(1, 0)]
+    );
+    assert_eq!(hexagonal::<Point>(2).len(), 18);
+}
@@ -0,0 +1,180 @@
+//! Running a step of Life on several threads.
+//!
+//! With hundreds of thousands of live cells, most of the time in
+//! `conway_step` goes to counting neighbors in one big hash map.
+//! This splits the work in two phases:
+//!
+//!   1. The live cells are split into one chunk per thread.  Each
+//!      thread counts the neighbors of its chunk into its own maps,
+//!      one map per shard, where the shard is picked from a hash of
+//!      the neighbor.
+//!   2. Each thread takes one shard, adds up that shard's counts from
+//!      all of the threads, and applies the rule.
+//!
+//! Each cell's count is the sum of the same numbers as in the serial
+//! version, so the result is exactly the same.
+
+use std::collections;
+use std::hash;
+use std::hash::Hasher;
+use std::ops;
+use std::thread;
+
+use crate::rule::Rule;
+use crate::topology::{self, Topology};
+
+/// Runs one step in Life, like `conway_step`, using `threads` threads.
+/// With one thread (or zero), this is just `conway_step`.
+///
+/// `std::thread::available_parallelism` is a good choice for the
+/// number of threads.
+pub fn conway_step_parallel<T, R>(
+    before: &collections::HashSet<T>,
+    neighbor_deltas: &[T],
+    rule: &R,
+    threads: usize
+) -> collections::HashSet<T>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash + Send + Sync,
+          R: Rule + Sync + ?Sized
+{
+    conway_step_in_parallel(before, neighbor_deltas, rule, &topology::Unbounded, threads)
+}
+
+/// Runs one step in Life on a board with the given topology, like
+/// `conway_step_in`, using `threads` threads.
+pub fn conway_step_in_parallel<T, R, P>(
+    before: &collections::HashSet<T>,
+    neighbor_deltas: &[T],
+    rule: &R,
+    topology: &P,
+    threads: usize
+) -> collections::HashSet<T>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash + Send + Sync,
+          R: Rule + Sync + ?Sized,
+          P: Topology<T> + Sync + ?Sized
+{
+    if threads <= 1 {
+        return crate::conway_step_in(before, neighbor_deltas, rule, topology);
+    }
+
+    let cells: Vec<T> = before.iter().cloned().collect();
+    let chunk_size = cells.len().div_ceil(threads).max(1);
+
+    // Phase 1: counts[i][j] is the counts from chunk i for shard j.
+    let counts: Vec<Vec<collections::HashMap<T, usize>>> = thread::scope(|scope| {
+        cells.chunks(chunk_size)
+            .map(|chunk| scope.spawn(move || count_into_shards(chunk, neighbor_deltas, topology, threads)))
+            .collect::<Vec<_>>()
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .collect()
+    });
+
+    // Phase 2: merge each shard and apply the rule.
+    let counts = &counts;
+    thread::scope(|scope| {
+        (0..threads)
+            .map(|shard| {
+                scope.spawn(move || {
+                    let mut merged: collections::HashMap<T, usize> = collections::HashMap::new();
+                    for chunk_counts in counts.iter() {
+                        for (&c, &n) in chunk_counts[shard].iter() {
+                            *merged.entry(c).or_insert(0) += n;
+                        }
+                    }
+                    crate::alive_after(before, &merged, rule).collect::<Vec<T>>()
+                })
+            })
+            .collect::<Vec<_>>()
+            .into_iter()
+            .flat_map(|handle| handle.join().unwrap())
+            .collect()
+    })
+}
+
+/// Counts the live neighbors of the cells, split into maps by shard.
+fn count_into_shards<T, P>(
+    cells: &[T],
+    neighbor_deltas: &[T],
+    topology: &P,
+    shards: usize
+) -> Vec<collections::HashMap<T, usize>>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          P: Topology<T> + ?Sized
+{
+    let mut result = vec![collections::HashMap::new(); shards];
+    for &c in cells {
+        for &d in neighbor_deltas.iter() {
+            if let Some(neighbor) = topology.normalize(c + d) {
+                *result[shard_of(&neighbor, shards)].entry(neighbor).or_insert(0) += 1;
+            }
+        }
+    }
+    result
+}
+
+/// Picks the shard for a cell.  The hash is the same in every
+/// thread, and much quicker than the one `HashMap` uses, which
+/// matters because it's done once for every neighbor counted.
+fn shard_of<T: hash::Hash>(cell: &T, shards: usize) -> usize {
+    let mut hasher = FnvHasher(0xcbf29ce484222325);
+    cell.hash(&mut hasher);
+    (hasher.finish() % shards as u64) as usize
+}
+
+/// The 64-bit FNV-1a hash.
+struct FnvHasher(u64);
+
+impl Hasher for FnvHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.0 = (self.0 ^ b as u64).wrapping_mul(0x100000001b3);
+        }
+    }
+}
+
+#[cfg(test)]
+use crate::point::{point, Point};
+
+#[test]
+fn test_parallel_matches_serial() {
+    let r_pentomino: collections::HashSet<Point> =
+        [point(1, 0), point(2, 0), point(0, 1), point(1, 1), point(1, 2)].iter().cloned().collect();
+    let deltas = crate::neighborhood::moore(1);
+    let rule = crate::LifeRule::conway();
+    let mut serial = r_pentomino.clone();
+    let mut parallel = r_pentomino;
+    for _ in 0..100 {
+        serial = crate::conway_step(&serial, &deltas, &rule);
+        parallel = conway_step_parallel(&parallel, &deltas, &rule, 4);
+        assert_eq!(parallel, serial);
+    }
+
+    // More threads than cells
+    let blinker: collections::HashSet<Point> = [point(0, 0), point(1, 0), point(2, 0)].iter().cloned().collect();
+    assert_eq!(
+        conway_step_parallel(&blinker, &deltas, &rule, 16),
+        [point(1, -1), point(1, 0), point(1, 1)].iter().cloned().collect()
+    );
+    assert!(conway_step_parallel(&collections::HashSet::new(), &deltas, &rule, 3).is_empty());
+}
+
+#[test]
+fn test_parallel_torus() {
+    let torus = topology::Torus(topology::Rectangle::new(0, 0, 8, 8));
+    let glider: collections::HashSet<Point> =
+        [point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)].iter().cloned().collect();
+    let deltas = crate::neighborhood::moore(1);
+    let rule = crate::LifeRule::conway();
+    let mut serial = glider.clone();
+    let mut parallel = glider;
+    for _ in 0..32 {
+        serial = crate::conway_step_in(&serial, &deltas, &rule, &torus);
+        parallel = conway_step_in_parallel(&parallel, &deltas, &rule, &torus, 3);
+        assert_eq!(parallel, serial);
+    }
+}
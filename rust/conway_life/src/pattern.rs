@@ -0,0 +1,455 @@
+//! Reading and writing 2-D patterns in the formats other Life
+//! programs use.
+//!
+//! RLE is the format used by Golly and most pattern collections:
+//!
+//! ```text
+//! #N Glider
+//! x = 3, y = 3, rule = B3/S23
+//! bo$2bo$3o!
+//! ```
+//!
+//! The plaintext `.cells` format draws the pattern with `.` for dead
+//! cells and `O` for live ones, after `!` comment lines:
+//!
+//! ```text
+//! !Name: Glider
+//! .O.
+//! ..O
+//! OOO
+//! ```
+//!
+//! Rows go down the page, so y increases from one row to the next.
+//! Plaintext files have no position, so the top-left corner of the
+//! pattern is always at (0, 0).  RLE files can have a Golly-style
+//! `#CXRLE Pos=x,y` line giving the position of the top-left corner,
+//! which the RLE writer adds when the pattern is not at (0, 0).
+
+use std::collections;
+use std::error;
+use std::fmt;
+use std::hash;
+
+use crate::generations::GenerationsRule;
+use crate::point::Planar;
+use crate::rule::{LifeRule, ParseRuleError};
+
+/// A pattern read from a file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Pattern<T: Eq + hash::Hash> {
+    /// The live cells.
+    pub cells: collections::HashSet<T>,
+    /// The name of the pattern, from an `#N` or `!Name:` line.
+    pub name: Option<String>,
+    /// The rule from the RLE header, as written.  It is checked with
+    /// the rule parsers, so it is either a `LifeRule` or a Generations
+    /// rule.  Plaintext files don't have a rule.
+    pub rule: Option<String>,
+}
+
+/// What was wrong with a pattern file.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum PatternErrorKind {
+    /// An RLE file had no `x = ..., y = ...` line before the cells.
+    MissingHeader,
+    /// The RLE header line could not be understood.
+    BadHeader(String),
+    /// The rule in the RLE header is not a rule.
+    BadRule(ParseRuleError),
+    /// A run count was too big, or had no tag after it to repeat.
+    BadRunCount(String),
+    /// A character that doesn't belong in the file.
+    UnexpectedChar(char),
+}
+
+/// An error in a pattern file, with the (1-based) line and column
+/// where it was found.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PatternError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: PatternErrorKind,
+}
+
+impl PatternError {
+    fn new(line: usize, column: usize, kind: PatternErrorKind) -> PatternError {
+        PatternError { line, column, kind }
+    }
+}
+
+impl fmt::Display for PatternError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, column {}: ", self.line, self.column)?;
+        match &self.kind {
+            PatternErrorKind::MissingHeader => write!(f, "missing \"x = ..., y = ...\" header"),
+            PatternErrorKind::BadHeader(text) => write!(f, "bad header: {}", text),
+            PatternErrorKind::BadRule(e) => write!(f, "bad rule: {}", e),
+            PatternErrorKind::BadRunCount(text) => write!(f, "bad run count {:?}", text),
+            PatternErrorKind::UnexpectedChar(c) => write!(f, "unexpected character {:?}", c),
+        }
+    }
+}
+
+impl error::Error for PatternError {}
+
+/// Reads a pattern in RLE format.
+pub fn read_rle<T: Planar + Eq + hash::Hash>(text: &str) -> Result<Pattern<T>, PatternError> {
+    let mut result = Pattern { cells: collections::HashSet::new(), name: None, rule: None };
+    let mut origin = (0, 0);
+    let mut seen_header = false;
+    let (mut x, mut y) = (0, 0);
+    let mut run = String::new();
+    // The line and column where the digits in `run` start.
+    let mut run_start = (0, 0);
+
+    for (line_index, line) in text.lines().enumerate() {
+        let line_number = line_index + 1;
+        if line.starts_with('#') {
+            if let Some(name) = line.strip_prefix("#N") {
+                result.name = Some(String::from(name.trim()));
+            } else if let Some(rest) = line.strip_prefix("#CXRLE") {
+                origin = parse_cxrle_pos(rest).ok_or_else(|| {
+                    PatternError::new(line_number, 1, PatternErrorKind::BadHeader(String::from(line)))
+                })?;
+            }
+            continue;
+        }
+        if !seen_header {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if !line.trim_start().starts_with('x') {
+                return Err(PatternError::new(line_number, 1, PatternErrorKind::MissingHeader));
+            }
+            let rule = parse_rle_header(line)
+                .map_err(|message| PatternError::new(line_number, 1, PatternErrorKind::BadHeader(message)))?;
+            if let Some(rule) = rule {
+                // The rule is a slice of the line, so its offset is where it starts.
+                let column = line[..rule.as_ptr() as usize - line.as_ptr() as usize].chars().count() + 1;
+                check_rule(rule)
+                    .map_err(|e| PatternError::new(line_number, column, PatternErrorKind::BadRule(e)))?;
+                result.rule = Some(String::from(rule));
+            }
+            seen_header = true;
+            continue;
+        }
+        for (column_index, c) in line.chars().enumerate() {
+            let error = |kind| PatternError::new(line_number, column_index + 1, kind);
+            if c.is_ascii_digit() {
+                if run.is_empty() {
+                    run_start = (line_number, column_index + 1);
+                }
+                run.push(c);
+                continue;
+            }
+            if c.is_whitespace() {
+                continue;
+            }
+            let bad_run =
+                |run: &str| PatternError::new(run_start.0, run_start.1, PatternErrorKind::BadRunCount(String::from(run)));
+            let count = if run.is_empty() {
+                1
+            } else if c == '!' {
+                return Err(bad_run(&run));
+            } else {
+                run.parse::<i32>().map_err(|_| bad_run(&run))?
+            };
+            run.clear();
+            match c {
+                'b' | '.' => x += count,
+                'o' | 'A' => {
+                    for i in 0..count {
+                        result.cells.insert(T::from_xy(origin.0 + x + i, origin.1 + y));
+                    }
+                    x += count;
+                },
+                '$' => {
+                    x = 0;
+                    y += count;
+                },
+                '!' => return Ok(result),
+                _ => return Err(error(PatternErrorKind::UnexpectedChar(c))),
+            }
+        }
+    }
+    if !seen_header {
+        return Err(PatternError::new(text.lines().count() + 1, 1, PatternErrorKind::MissingHeader));
+    }
+    if !run.is_empty() {
+        return Err(PatternError::new(run_start.0, run_start.1, PatternErrorKind::BadRunCount(run)));
+    }
+    Ok(result)
+}
+
+/// Checks that a rule from an RLE header parses.  Rules with three
+/// parts, like "B2/S/C3", are Generations rules.
+fn check_rule(rule: &str) -> Result<(), ParseRuleError> {
+    if rule.split('/').count() == 3 {
+        rule.parse::<GenerationsRule<LifeRule>>().map(|_| ())
+    } else {
+        rule.parse::<LifeRule>().map(|_| ())
+    }
+}
+
+/// Parses the RLE header line, like "x = 3, y = 3, rule = B3/S23",
+/// returning the rule, if there is one.
+fn parse_rle_header(line: &str) -> Result<Option<&str>, String> {
+    let mut rule = None;
+    let mut has_x = false;
+    let mut has_y = false;
+    for item in line.split(',') {
+        let mut key_value = item.splitn(2, '=');
+        let key = key_value.next().unwrap().trim();
+        let value = key_value.next().ok_or_else(|| format!("no '=' in {:?}", item.trim()))?.trim();
+        match key {
+            "x" | "y" => {
+                value.parse::<u32>().map_err(|_| format!("{} is not a size: {:?}", key, value))?;
+                has_x |= key == "x";
+                has_y |= key == "y";
+            },
+            "rule" => rule = Some(value),
+            _ => return Err(format!("unknown key {:?}", key)),
+        }
+    }
+    if has_x && has_y { Ok(rule) } else { Err(String::from("x and y are both required")) }
+}
+
+/// Parses the rest of a "#CXRLE Pos=-3,4 Gen=0" line, returning the position.
+fn parse_cxrle_pos(text: &str) -> Option<(i32, i32)> {
+    match text.split_whitespace().find(|item| item.starts_with("Pos=")) {
+        None => Some((0, 0)),
+        Some(item) => {
+            let mut coords = item["Pos=".len()..].split(',');
+            let x = coords.next()?.parse().ok()?;
+            let y = coords.next()?.parse().ok()?;
+            if coords.next().is_some() { None } else { Some((x, y)) }
+        }
+    }
+}
+
+/// The smallest and largest coordinates of the cells: (min_x, min_y, max_x, max_y).
+fn bounding_box<T: Planar>(cells: &collections::HashSet<T>) -> Option<(i32, i32, i32, i32)> {
+    cells.iter().fold(None, |bounds, c| {
+        Some(match bounds {
+            None => (c.x(), c.y(), c.x(), c.y()),
+            Some((x0, y0, x1, y1)) => (x0.min(c.x()), y0.min(c.y()), x1.max(c.x()), y1.max(c.y())),
+        })
+    })
+}
+
+/// Writes a pattern in RLE format.  The name and rule are included
+/// if they are there.
+pub fn write_rle<T: Planar + Eq + hash::Hash>(pattern: &Pattern<T>) -> String {
+    let mut result = String::new();
+    if let Some(name) = &pattern.name {
+        result.push_str(&format!("#N {}\n", name));
+    }
+    let (x0, y0, x1, y1) = bounding_box(&pattern.cells).unwrap_or((0, 0, -1, -1));
+    if (x0, y0) != (0, 0) {
+        result.push_str(&format!("#CXRLE Pos={},{}\n", x0, y0));
+    }
+    result.push_str(&format!("x = {}, y = {}", x1 - x0 + 1, y1 - y0 + 1));
+    if let Some(rule) = &pattern.rule {
+        result.push_str(&format!(", rule = {}", rule));
+    }
+    result.push('\n');
+
+    // Make a list of (count, tag) runs, leaving off dead cells at the
+    // ends of rows, and merging the ends of empty rows.
+    let mut runs: Vec<(i32, char)> = Vec::new();
+    let mut push = |count: i32, tag: char| {
+        match runs.last_mut() {
+            Some((n, t)) if *t == tag => *n += count,
+            _ => runs.push((count, tag)),
+        }
+    };
+    for y in y0..=y1 {
+        if y != y0 {
+            push(1, '$');
+        }
+        let mut x = x0;
+        while x <= x1 {
+            let alive = pattern.cells.contains(&T::from_xy(x, y));
+            let mut end = x;
+            while end <= x1 && pattern.cells.contains(&T::from_xy(end, y)) == alive {
+                end += 1;
+            }
+            if alive || end <= x1 {
+                push(end - x, if alive { 'o' } else { 'b' });
+            }
+            x = end;
+        }
+    }
+    push(1, '!');
+
+    // Write the runs, keeping lines to 70 characters.
+    let mut line = String::new();
+    for (count, tag) in runs {
+        let item = if count == 1 { tag.to_string() } else { format!("{}{}", count, tag) };
+        if 70 < line.len() + item.len() {
+            result.push_str(&line);
+            result.push('\n');
+            line.clear();
+        }
+        line.push_str(&item);
+    }
+    result.push_str(&line);
+    result.push('\n');
+    result
+}
+
+/// Reads a pattern in plaintext `.cells` format.  The top-left
+/// corner is at (0, 0).
+pub fn read_cells<T: Planar + Eq + hash::Hash>(text: &str) -> Result<Pattern<T>, PatternError> {
+    let mut result = Pattern { cells: collections::HashSet::new(), name: None, rule: None };
+    let mut y = 0;
+    for (line_index, line) in text.lines().enumerate() {
+        if let Some(comment) = line.strip_prefix('!') {
+            if let Some(name) = comment.strip_prefix("Name:") {
+                result.name = Some(String::from(name.trim()));
+            }
+            continue;
+        }
+        for (x, c) in line.trim_end().chars().enumerate() {
+            match c {
+                '.' => {},
+                'O' | '*' => {
+                    result.cells.insert(T::from_xy(x as i32, y));
+                },
+                _ => return Err(PatternError::new(line_index + 1, x + 1, PatternErrorKind::UnexpectedChar(c))),
+            }
+        }
+        y += 1;
+    }
+    Ok(result)
+}
+
+/// Writes a pattern in plaintext `.cells` format.  The pattern is
+/// moved so its top-left corner is at (0, 0).
+pub fn write_cells<T: Planar + Eq + hash::Hash>(pattern: &Pattern<T>) -> String {
+    let mut result = String::new();
+    if let Some(name) = &pattern.name {
+        result.push_str(&format!("!Name: {}\n", name));
+    }
+    if let Some((x0, y0, x1, y1)) = bounding_box(&pattern.cells) {
+        for y in y0..=y1 {
+            for x in x0..=x1 {
+                result.push(if pattern.cells.contains(&T::from_xy(x, y)) { 'O' } else { '.' });
+            }
+            result.push('\n');
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+use crate::point::{point, Point};
+
+#[cfg(test)]
+fn glider() -> collections::HashSet<Point> {
+    [point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)].iter().cloned().collect()
+}
+
+#[test]
+fn test_read_rle() {
+    let text = "#N Glider\n#C A comment\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n";
+    let pattern: Pattern<Point> = read_rle(text).unwrap();
+    assert_eq!(pattern.cells, glider());
+    assert_eq!(pattern.name, Some(String::from("Glider")));
+    assert_eq!(pattern.rule, Some(String::from("B3/S23")));
+}
+
+#[test]
+fn test_read_rle_gosper_gun() {
+    let text = "\
+#N Gosper glider gun
+x = 36, y = 9, rule = B3/S23
+24bo$22bobo$12b2o6b2o12b2o$11bo3bo4b2o12b2o$2o8bo5bo3b2o$2o8bo3bob2o4b
+obo$10bo5bo7bo$11bo3bo$12b2o!
+";
+    let pattern: Pattern<Point> = read_rle(text).unwrap();
+    assert_eq!(pattern.cells.len(), 36);
+    assert!(pattern.cells.contains(&point(24, 0)));
+    assert!(pattern.cells.contains(&point(35, 3)));
+    assert!(pattern.cells.contains(&point(13, 8)));
+}
+
+#[test]
+fn test_rle_round_trip() {
+    let moved: collections::HashSet<Point> = glider().iter().map(|&c| c + point(-7, 12)).collect();
+    let pattern = Pattern { cells: moved, name: Some(String::from("Glider")), rule: Some(String::from("B3/S23")) };
+    let text = write_rle(&pattern);
+    assert_eq!(text, "#N Glider\n#CXRLE Pos=-7,12\nx = 3, y = 3, rule = B3/S23\nbo$2bo$3o!\n");
+    assert_eq!(read_rle(&text), Ok(pattern));
+
+    // Empty rows, and a line that needs wrapping
+    let mut cells: collections::HashSet<Point> = (0..50).map(|x| point(x * 2, 0)).collect();
+    cells.insert(point(0, 5));
+    let pattern = Pattern { cells, name: None, rule: None };
+    let text = write_rle(&pattern);
+    assert!(text.lines().all(|line| line.len() <= 70));
+    assert!(text.contains("5$o!"));
+    assert_eq!(read_rle(&text), Ok(pattern));
+}
+
+#[test]
+fn test_rle_errors() {
+    assert_eq!(
+        read_rle::<Point>("bo$2bo$3o!"),
+        Err(PatternError::new(1, 1, PatternErrorKind::MissingHeader))
+    );
+    assert_eq!(
+        read_rle::<Point>("#N Empty\n"),
+        Err(PatternError::new(2, 1, PatternErrorKind::MissingHeader))
+    );
+    assert_eq!(
+        read_rle::<Point>("x = 3\nbo$2bo$3o!"),
+        Err(PatternError::new(1, 1, PatternErrorKind::BadHeader(String::from("x and y are both required"))))
+    );
+    let error = read_rle::<Point>("x = 3, y = 3\nbo$2bo\n$3oz!").unwrap_err();
+    assert_eq!(error, PatternError::new(3, 4, PatternErrorKind::UnexpectedChar('z')));
+    assert_eq!(error.to_string(), "line 3, column 4: unexpected character 'z'");
+
+    let error = read_rle::<Point>("x = 3, y = 3, rule = B3/S2x\nbo$2bo$3o!").unwrap_err();
+    assert_eq!(
+        error,
+        PatternError::new(1, 22, PatternErrorKind::BadRule(ParseRuleError::BadCount { part: 'S', found: 'x' }))
+    );
+    assert_eq!(error.to_string(), "line 1, column 22: bad rule: 'x' in S part is not a neighbor count");
+    assert_eq!(
+        read_rle::<Point>("x = 3, y = 3, rule = B2/S/C1\no!"),
+        Err(PatternError::new(1, 22, PatternErrorKind::BadRule(ParseRuleError::BadStateCount(String::from("1")))))
+    );
+    assert!(read_rle::<Point>("x = 3, y = 3, rule = B2/S/C3\no!").is_ok());
+
+    // Run counts are reported where their digits start.
+    assert_eq!(
+        read_rle::<Point>("x = 3, y = 3\nbo$99999999999o!"),
+        Err(PatternError::new(2, 4, PatternErrorKind::BadRunCount(String::from("99999999999"))))
+    );
+    assert_eq!(
+        read_rle::<Point>("x = 3, y = 3\nbo$2bo$3o3!"),
+        Err(PatternError::new(2, 10, PatternErrorKind::BadRunCount(String::from("3"))))
+    );
+    assert_eq!(
+        read_rle::<Point>("x = 3, y = 3\nbo$2bo$3o12"),
+        Err(PatternError::new(2, 10, PatternErrorKind::BadRunCount(String::from("12"))))
+    );
+}
+
+#[test]
+fn test_cells_round_trip() {
+    let text = "!Name: Glider\n!\n.O.\n..O\nOOO\n";
+    let pattern: Pattern<Point> = read_cells(text).unwrap();
+    assert_eq!(pattern.cells, glider());
+    assert_eq!(pattern.name, Some(String::from("Glider")));
+    assert_eq!(write_cells(&pattern), "!Name: Glider\n.O.\n..O\nOOO\n");
+}
+
+#[test]
+fn test_cells_errors() {
+    assert_eq!(
+        read_cells::<Point>("!Name: Bad\n.O.\n.#O\n"),
+        Err(PatternError::new(3, 2, PatternErrorKind::UnexpectedChar('#')))
+    );
+}
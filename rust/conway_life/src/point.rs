@@ -0,0 +1,148 @@
+//! Cell names made of integer coordinates.
+//!
+//! `Point` is a ready-made cell name for square grids.  The `Planar`
+//! trait lets code that needs x and y coordinates, like the bounded
+//! and toroidal topologies, work with other 2-D cell names too, such
+//! as the axial hex coordinates in day 24.
+//!
+//! `Vector` is the same idea for any number of dimensions, and
+//! `PointN` is a ready-made cell name for N-dimensional grids.
+
+use std::ops;
+
+/// A cell name with an x and a y coordinate.
+pub trait Planar: Copy {
+    fn x(&self) -> i32;
+    fn y(&self) -> i32;
+    fn from_xy(x: i32, y: i32) -> Self;
+}
+
+/// A cell name with a fixed number of integer coordinates.
+pub trait Vector: Copy {
+    /// The number of coordinates.
+    const DIMS: usize;
+
+    /// Returns the coordinate on one axis, which is less than `DIMS`.
+    fn coord(&self, axis: usize) -> i32;
+
+    /// Makes a cell name from its coordinates.  There must be
+    /// `DIMS` of them.
+    fn from_coords(coords: &[i32]) -> Self;
+}
+
+/// A cell on a square grid.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Shorthand for making a Point.
+pub fn point(x: i32, y: i32) -> Point {
+    Point { x, y }
+}
+
+impl Planar for Point {
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn from_xy(x: i32, y: i32) -> Point {
+        point(x, y)
+    }
+}
+
+impl Vector for Point {
+    const DIMS: usize = 2;
+
+    fn coord(&self, axis: usize) -> i32 {
+        match axis {
+            0 => self.x,
+            1 => self.y,
+            _ => panic!("bad axis for Point: {}", axis),
+        }
+    }
+
+    fn from_coords(coords: &[i32]) -> Point {
+        assert_eq!(coords.len(), 2);
+        point(coords[0], coords[1])
+    }
+}
+
+impl ops::Add for Point {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        point(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl ops::Sub for Point {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        point(self.x - other.x, self.y - other.y)
+    }
+}
+
+/// A cell on an N-dimensional grid, like the pocket dimension in day 17.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct PointN<const N: usize>(pub [i32; N]);
+
+impl<const N: usize> Vector for PointN<N> {
+    const DIMS: usize = N;
+
+    fn coord(&self, axis: usize) -> i32 {
+        self.0[axis]
+    }
+
+    fn from_coords(coords: &[i32]) -> PointN<N> {
+        let mut result = [0; N];
+        result.copy_from_slice(coords);
+        PointN(result)
+    }
+}
+
+impl<const N: usize> ops::Add for PointN<N> {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let mut result = self.0;
+        for (r, o) in result.iter_mut().zip(other.0.iter()) {
+            *r += o;
+        }
+        PointN(result)
+    }
+}
+
+impl<const N: usize> ops::Sub for PointN<N> {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        let mut result = self.0;
+        for (r, o) in result.iter_mut().zip(other.0.iter()) {
+            *r -= o;
+        }
+        PointN(result)
+    }
+}
+
+#[test]
+fn test_point_arithmetic() {
+    assert_eq!(point(1, 2) + point(4, 8), point(5, 10));
+    assert_eq!(point(1, 2) - point(4, 8), point(-3, -6));
+}
+
+#[test]
+fn test_point_n() {
+    let a = PointN([1, 2, 3, 4]);
+    let b = PointN([10, 20, 30, 40]);
+    assert_eq!(a + b, PointN([11, 22, 33, 44]));
+    assert_eq!(a - b, PointN([-9, -18, -27, -36]));
+    assert_eq!(PointN::<4>::from_coords(&[5, 6, 7, 8]).coord(2), 7);
+    assert_eq!(Point::from_coords(&[5, 6]), point(5, 6));
+}
@@ -0,0 +1,249 @@
+//! Searching backward in time: finding a board that turns into a
+//! given board after one step.
+//!
+//! The search only looks at predecessors whose live cells are inside
+//! a given box, so if it finds none, that proves there isn't one in
+//! the box, not that the target is a Garden of Eden.
+//!
+//! It's a backtracking search that decides the cells of the box one
+//! at a time, in rows.  Every cell near the box keeps a count of its
+//! neighbors that are known to be alive, and of the ones not decided
+//! yet.  After each decision, the cells whose counts changed are
+//! checked to see if the rule could still give them the state they
+//! have in the target; if not, the search backs up.
+
+use std::collections;
+use std::hash;
+use std::ops;
+
+use crate::point::Planar;
+use crate::rule::Rule;
+use crate::topology::Rectangle;
+
+/// What is known about a cell in the predecessor.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Cell {
+    Unknown,
+    Dead,
+    Alive,
+}
+
+/// The state of a search.  Cells are numbered in rows across the
+/// grid, which is the search box with a margin around it wide enough
+/// to hold every cell that could be alive after the step.
+struct Search<'a, R: ?Sized> {
+    rule: &'a R,
+    /// The state of each grid cell in the predecessor.  Cells outside
+    /// the search box are always dead.
+    cells: Vec<Cell>,
+    /// Is each grid cell alive in the target?
+    target: Vec<bool>,
+    /// For each grid cell, the number of its neighbors known to be alive.
+    live: Vec<usize>,
+    /// For each grid cell, the number of its neighbors not decided yet.
+    unknown: Vec<usize>,
+    /// For each grid cell, the grid cells whose counts it is part of.
+    counted_by: Vec<Vec<usize>>,
+    /// The grid cells in the search box, in the order they are decided.
+    order: Vec<usize>,
+}
+
+impl<'a, R: Rule + ?Sized> Search<'a, R> {
+    /// Could the cell still end up in its target state?
+    fn is_possible(&self, index: usize) -> bool {
+        let states: &[bool] = match self.cells[index] {
+            Cell::Unknown => &[false, true],
+            Cell::Dead => &[false],
+            Cell::Alive => &[true],
+        };
+        let low = self.live[index];
+        let high = low + self.unknown[index];
+        states.iter().any(|&was_alive| {
+            (low..=high).any(|n| (n != 0 && self.rule.is_alive(was_alive, n)) == self.target[index])
+        })
+    }
+
+    /// Decides one cell, and returns false if that makes the target
+    /// impossible.
+    fn set(&mut self, index: usize, state: Cell) -> bool {
+        self.cells[index] = state;
+        for i in 0..self.counted_by[index].len() {
+            let other = self.counted_by[index][i];
+            self.unknown[other] -= 1;
+            if state == Cell::Alive {
+                self.live[other] += 1;
+            }
+        }
+        self.is_possible(index) && self.counted_by[index].iter().all(|&other| self.is_possible(other))
+    }
+
+    /// Undoes `set`.
+    fn unset(&mut self, index: usize) {
+        let was_alive = self.cells[index] == Cell::Alive;
+        self.cells[index] = Cell::Unknown;
+        for &other in self.counted_by[index].iter() {
+            self.unknown[other] += 1;
+            if was_alive {
+                self.live[other] -= 1;
+            }
+        }
+    }
+
+    /// Decides the cells from `position` in the order onward.
+    /// Returns true, with every cell decided, if it finds a predecessor.
+    fn solve(&mut self, position: usize) -> bool {
+        if position == self.order.len() {
+            return true;
+        }
+        let index = self.order[position];
+        for &state in [Cell::Dead, Cell::Alive].iter() {
+            if self.set(index, state) && self.solve(position + 1) {
+                return true;
+            }
+            self.unset(index);
+        }
+        false
+    }
+}
+
+/// Looks for a board, with all of its live cells in the search box,
+/// that `conway_step` turns into the target.  Returns None if there
+/// isn't one.
+///
+/// The search takes time exponential in the size of the box, so
+/// boxes should be small.
+pub fn find_predecessor<T, R>(
+    target: &collections::HashSet<T>,
+    neighbor_deltas: &[T],
+    rule: &R,
+    search_box: &Rectangle
+) -> Option<collections::HashSet<T>>
+    where T: Planar + ops::Add<Output = T> + Eq + hash::Hash,
+          R: Rule + ?Sized
+{
+    // The grid is the box plus a margin as wide as the reach of the
+    // neighborhood.  Cells outside of it have no neighbors in the box.
+    let reach = neighbor_deltas.iter().map(|d| d.x().abs().max(d.y().abs())).max().unwrap_or(0);
+    let grid = Rectangle::new(
+        search_box.min_x - reach,
+        search_box.min_y - reach,
+        search_box.width + 2 * reach,
+        search_box.height + 2 * reach,
+    );
+    if !target.iter().all(|c| grid.contains(c)) {
+        return None;
+    }
+    let index_of = |c: T| -> Option<usize> {
+        if grid.contains(&c) {
+            Some(((c.y() - grid.min_y) * grid.width + (c.x() - grid.min_x)) as usize)
+        } else {
+            None
+        }
+    };
+    let cell_at = |i: usize| -> T {
+        T::from_xy(grid.min_x + i as i32 % grid.width, grid.min_y + i as i32 / grid.width)
+    };
+
+    let size = (grid.width * grid.height) as usize;
+    let mut search = Search {
+        rule,
+        cells: vec![Cell::Dead; size],
+        target: (0..size).map(|i| target.contains(&cell_at(i))).collect(),
+        live: vec![0; size],
+        unknown: vec![0; size],
+        counted_by: vec![Vec::new(); size],
+        order: Vec::new(),
+    };
+    for i in 0..size {
+        let c = cell_at(i);
+        if search_box.contains(&c) {
+            search.cells[i] = Cell::Unknown;
+            search.order.push(i);
+            search.counted_by[i] = neighbor_deltas.iter().filter_map(|&d| index_of(c + d)).collect();
+            for &other in search.counted_by[i].iter() {
+                search.unknown[other] += 1;
+            }
+        }
+    }
+
+    // A live target cell that no cell in the box is a neighbor of can't
+    // come alive, and the search never checks it, so rule it out here.
+    if (0..size).any(|i| search.target[i] && search.unknown[i] == 0) {
+        return None;
+    }
+    if !search.solve(0) {
+        return None;
+    }
+    let result: collections::HashSet<T> =
+        (0..size).filter(|&i| search.cells[i] == Cell::Alive).map(cell_at).collect();
+    assert!(crate::conway_step(&result, neighbor_deltas, rule) == *target, "predecessor search is broken");
+    Some(result)
+}
+
+#[cfg(test)]
+use crate::point::{point, Point};
+
+#[cfg(test)]
+fn set_of(cells: &[(i32, i32)]) -> collections::HashSet<Point> {
+    cells.iter().map(|&(x, y)| point(x, y)).collect()
+}
+
+#[test]
+fn test_blinker_predecessor() {
+    let deltas = crate::neighborhood::moore(1);
+    let rule = crate::LifeRule::conway();
+    let target = set_of(&[(0, 0), (1, 0), (2, 0)]);
+    let found = find_predecessor(&target, &deltas, &rule, &Rectangle::new(-1, -2, 5, 5)).unwrap();
+    assert_eq!(crate::conway_step(&found, &deltas, &rule), target);
+
+    // Nothing in a 1 x 1 box survives.
+    assert_eq!(find_predecessor(&target, &deltas, &rule, &Rectangle::new(1, 0, 1, 1)), None);
+    // A box too far away can't reach the target.
+    assert_eq!(find_predecessor(&target, &deltas, &rule, &Rectangle::new(10, 10, 3, 3)), None);
+}
+
+#[test]
+fn test_uncounted_target_cell() {
+    // (1, 1) is in the margin of the box, but it isn't a von Neumann
+    // neighbor of (0, 0), so nothing in the box can bring it alive.
+    let deltas = crate::neighborhood::von_neumann(1);
+    let rule: crate::LifeRule = "B1/S".parse().unwrap();
+    let target = set_of(&[(1, 1)]);
+    assert_eq!(find_predecessor(&target, &deltas, &rule, &Rectangle::new(0, 0, 1, 1)), None);
+}
+
+#[test]
+fn test_matches_brute_force() {
+    // Every board in a 3 x 3 box, and what it turns into
+    let deltas = crate::neighborhood::moore(1);
+    let rule = crate::LifeRule::conway();
+    let search_box = Rectangle::new(0, 0, 3, 3);
+    let mut reachable = collections::HashSet::new();
+    for bits in 0..(1 << 9) {
+        let board: collections::HashSet<Point> =
+            (0..9).filter(|i| bits & (1 << i) != 0).map(|i| point(i % 3, i / 3)).collect();
+        let mut after: Vec<Point> = crate::conway_step(&board, &deltas, &rule).into_iter().collect();
+        after.sort();
+        reachable.insert(after);
+    }
+
+    // Every target in the 3 x 3 box has a predecessor exactly when
+    // brute force found one.
+    for bits in 0..(1 << 9) {
+        let mut target: Vec<Point> = (0..9).filter(|i| bits & (1 << i) != 0).map(|i| point(i % 3, i / 3)).collect();
+        target.sort();
+        let target_set: collections::HashSet<Point> = target.iter().cloned().collect();
+        let found = find_predecessor(&target_set, &deltas, &rule, &search_box);
+        assert_eq!(found.is_some(), reachable.contains(&target), "target {:?}", target);
+    }
+}
+
+#[test]
+fn test_other_rules() {
+    // In HighLife (B36/S23), six neighbors give a birth.
+    let deltas = crate::neighborhood::moore(1);
+    let rule: crate::LifeRule = "B36/S23".parse().unwrap();
+    let target = set_of(&[(1, 1)]);
+    let found = find_predecessor(&target, &deltas, &rule, &Rectangle::new(0, 0, 3, 3)).unwrap();
+    assert_eq!(crate::conway_step(&found, &deltas, &rule), target);
+}
@@ -0,0 +1,309 @@
+//! Drawing 2-D boards, as text or as images.
+//!
+//! Both kinds of drawing show a fixed viewport: a `Rectangle` of the
+//! board, so that the frames of a simulation line up with each other.
+//! Rows go down the page, so y increases from one row to the next,
+//! the same as in the `pattern` module.
+//!
+//! Hex boards use axial coordinates, as in day 24, where y increases
+//! to the northeast.  So their rows go up the page instead, and each
+//! row is shifted half a cell from the one below, like bricks in a
+//! wall.  To keep the viewport rectangular, its x range is in columns,
+//! where the column of cell (x, y) is `x + y.div_euclid(2)`.
+//!
+//! Images are written in the binary PPM (color) and PGM (gray) formats,
+//! which most image viewers and tools like ImageMagick can read.
+
+use std::fs;
+use std::io;
+use std::marker;
+use std::path;
+
+use crate::board::Board;
+use crate::point::Planar;
+use crate::topology::Rectangle;
+
+/// How cells are arranged on the page.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Layout {
+    Square,
+    Hex,
+}
+
+impl Layout {
+    /// Returns the row of the board shown on a line of the viewport,
+    /// counting lines from the top.
+    fn row_at(self, view: &Rectangle, line: i32) -> i32 {
+        match self {
+            Layout::Square => view.min_y + line,
+            Layout::Hex => view.min_y + view.height - 1 - line,
+        }
+    }
+
+    /// Returns the cell shown at a row and column of the viewport.
+    fn cell_at<T: Planar>(self, column: i32, row: i32) -> T {
+        match self {
+            Layout::Square => T::from_xy(column, row),
+            Layout::Hex => T::from_xy(column - row.div_euclid(2), row),
+        }
+    }
+
+    /// Is the row shifted half a cell to the right?
+    fn is_shifted(self, row: i32) -> bool {
+        self == Layout::Hex && row.rem_euclid(2) == 1
+    }
+}
+
+/// Draws the part of the board in the viewport as text, with `#` for
+/// live cells and `.` for dead ones, like the puzzle descriptions.
+/// Hex cells are separated by spaces, so that every other row can be
+/// shifted by one character.
+pub fn ascii<T: Planar, B: Board<T>>(board: &B, view: &Rectangle, layout: Layout) -> String {
+    let mut result = String::new();
+    for row in (0..view.height).map(|line| layout.row_at(view, line)) {
+        let mut line = String::new();
+        if layout.is_shifted(row) {
+            line.push(' ');
+        }
+        for column in view.min_x..view.min_x + view.width {
+            if layout == Layout::Hex && column != view.min_x {
+                line.push(' ');
+            }
+            line.push(if board.contains(&layout.cell_at(column, row)) { '#' } else { '.' });
+        }
+        result.push_str(&line);
+        result.push('\n');
+    }
+    result
+}
+
+/// A color, as red, green, and blue.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// The brightness of the color, for gray images.
+    pub fn gray(self) -> u8 {
+        ((self.0 as u32 * 299 + self.1 as u32 * 587 + self.2 as u32 * 114) / 1000) as u8
+    }
+}
+
+/// How to draw a board as an image.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Style {
+    /// The width and height of a cell, in pixels.
+    pub scale: usize,
+    pub alive: Rgb,
+    pub dead: Rgb,
+    pub layout: Layout,
+}
+
+impl Default for Style {
+    /// White cells on black, four pixels each, on a square grid.
+    fn default() -> Style {
+        Style { scale: 4, alive: Rgb(255, 255, 255), dead: Rgb(0, 0, 0), layout: Layout::Square }
+    }
+}
+
+/// The file formats that images can be written in.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ImageFormat {
+    /// Binary PPM, with full color.
+    Ppm,
+    /// Binary PGM, with the colors turned to gray.
+    Pgm,
+}
+
+impl ImageFormat {
+    /// The usual file name extension.
+    pub fn extension(self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Pgm => "pgm",
+        }
+    }
+}
+
+/// A picture of a board.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Image {
+    pub width: usize,
+    pub height: usize,
+    /// The pixels, one row at a time, starting at the top left.
+    pub pixels: Vec<Rgb>,
+}
+
+impl Image {
+    /// Draws the part of the board in the viewport.  Hex images are
+    /// half a cell wider than square ones, to make room for the
+    /// shifted rows.
+    pub fn render<T: Planar, B: Board<T>>(board: &B, view: &Rectangle, style: &Style) -> Image {
+        let scale = style.scale;
+        let extra = if style.layout == Layout::Hex { scale / 2 } else { 0 };
+        let width = view.width as usize * scale + extra;
+        let height = view.height as usize * scale;
+        let mut pixels = vec![style.dead; width * height];
+        for line in 0..view.height {
+            let row = style.layout.row_at(view, line);
+            let shift = if style.layout.is_shifted(row) { extra } else { 0 };
+            for column in 0..view.width {
+                let cell: T = style.layout.cell_at(view.min_x + column, row);
+                if !board.contains(&cell) {
+                    continue;
+                }
+                for y in 0..scale {
+                    let start = (line as usize * scale + y) * width + shift + column as usize * scale;
+                    for pixel in pixels[start..start + scale].iter_mut() {
+                        *pixel = style.alive;
+                    }
+                }
+            }
+        }
+        Image { width, height, pixels }
+    }
+
+    /// Writes the image in the given format.
+    pub fn write<W: io::Write>(&self, format: ImageFormat, out: &mut W) -> io::Result<()> {
+        match format {
+            ImageFormat::Ppm => {
+                write!(out, "P6\n{} {}\n255\n", self.width, self.height)?;
+                let bytes: Vec<u8> = self.pixels.iter().flat_map(|p| vec![p.0, p.1, p.2]).collect();
+                out.write_all(&bytes)
+            },
+            ImageFormat::Pgm => {
+                write!(out, "P5\n{} {}\n255\n", self.width, self.height)?;
+                let bytes: Vec<u8> = self.pixels.iter().map(|p| p.gray()).collect();
+                out.write_all(&bytes)
+            },
+        }
+    }
+}
+
+/// Writes a sequence of frames into a directory, named
+/// `frame-00000.ppm`, `frame-00001.ppm`, and so on.  Tools like
+/// ffmpeg can turn them into a movie.
+pub struct FrameWriter<T> {
+    directory: path::PathBuf,
+    view: Rectangle,
+    style: Style,
+    format: ImageFormat,
+    count: usize,
+    cell: marker::PhantomData<T>,
+}
+
+impl<T: Planar> FrameWriter<T> {
+    /// Creates the directory, if it's not there already.
+    pub fn new<P: AsRef<path::Path>>(
+        directory: P,
+        view: Rectangle,
+        style: Style,
+        format: ImageFormat
+    ) -> io::Result<FrameWriter<T>> {
+        fs::create_dir_all(directory.as_ref())?;
+        Ok(FrameWriter {
+            directory: directory.as_ref().to_path_buf(),
+            view,
+            style,
+            format,
+            count: 0,
+            cell: marker::PhantomData,
+        })
+    }
+
+    /// The number of frames written so far.
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// Writes the next frame, and returns the name of its file.
+    pub fn write_frame<B: Board<T>>(&mut self, board: &B) -> io::Result<path::PathBuf> {
+        let file_name = self.directory.join(format!("frame-{:05}.{}", self.count, self.format.extension()));
+        let image = Image::render(board, &self.view, &self.style);
+        let mut out = io::BufWriter::new(fs::File::create(&file_name)?);
+        image.write(self.format, &mut out)?;
+        self.count += 1;
+        Ok(file_name)
+    }
+}
+
+#[cfg(test)]
+use crate::point::{point, Point};
+
+#[cfg(test)]
+use std::collections;
+
+#[cfg(test)]
+fn glider() -> collections::HashSet<Point> {
+    [point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)].iter().cloned().collect()
+}
+
+#[test]
+fn test_ascii_square() {
+    let view = Rectangle::new(-1, 0, 5, 3);
+    assert_eq!(ascii(&glider(), &view, Layout::Square), "..#..\n...#.\n.###.\n");
+}
+
+#[test]
+fn test_ascii_hex() {
+    // A cell and its six neighbors
+    let cells: collections::HashSet<Point> =
+        [point(0, 0), point(1, 0), point(-1, 0), point(0, 1), point(-1, 1), point(0, -1), point(1, -1)]
+            .iter().cloned().collect();
+    let view = Rectangle::new(-2, -1, 5, 3);
+    assert_eq!(ascii(&cells, &view, Layout::Hex), " . # # . .\n. # # # .\n . # # . .\n");
+
+    // A cell with its northeast and east neighbors: northeast is up
+    // and to the right.
+    let cells: collections::HashSet<Point> = [point(0, 0), point(0, 1), point(1, 0)].iter().cloned().collect();
+    assert_eq!(ascii(&cells, &Rectangle::new(-1, 0, 3, 2), Layout::Hex), " . # .\n. # #\n");
+}
+
+#[test]
+fn test_render() {
+    let style = Style { scale: 2, alive: Rgb(255, 0, 0), dead: Rgb(0, 0, 255), layout: Layout::Square };
+    let image = Image::render(&glider(), &Rectangle::new(0, 0, 3, 3), &style);
+    assert_eq!((image.width, image.height), (6, 6));
+    let red_pixels = image.pixels.iter().filter(|&&p| p == Rgb(255, 0, 0)).count();
+    assert_eq!(red_pixels, 5 * 4);
+    assert_eq!(image.pixels[2], Rgb(255, 0, 0));
+    assert_eq!(image.pixels[6 + 3], Rgb(255, 0, 0));
+    assert_eq!(image.pixels[0], Rgb(0, 0, 255));
+
+    let mut ppm = Vec::new();
+    image.write(ImageFormat::Ppm, &mut ppm).unwrap();
+    assert!(ppm.starts_with(b"P6\n6 6\n255\n"));
+    assert_eq!(ppm.len(), b"P6\n6 6\n255\n".len() + 36 * 3);
+
+    let mut pgm = Vec::new();
+    image.write(ImageFormat::Pgm, &mut pgm).unwrap();
+    assert!(pgm.starts_with(b"P5\n6 6\n255\n"));
+    assert_eq!(pgm[b"P5\n6 6\n255\n".len()], Rgb(0, 0, 255).gray());
+}
+
+#[test]
+fn test_render_hex() {
+    let style = Style { scale: 2, layout: Layout::Hex, ..Style::default() };
+    let cells: collections::HashSet<Point> = [point(0, 1)].iter().cloned().collect();
+    let image = Image::render(&cells, &Rectangle::new(0, 0, 2, 2), &style);
+    assert_eq!((image.width, image.height), (5, 4));
+    // y = 1 is the top row, and it is shifted by one pixel.
+    let alive: Vec<usize> =
+        (0..image.pixels.len()).filter(|&i| image.pixels[i] == style.alive).collect();
+    assert_eq!(alive, vec![1, 2, 6, 7]);
+}
+
+#[test]
+fn test_frame_writer() {
+    let directory = std::env::temp_dir().join(format!("conway_life_frames_{}", std::process::id()));
+    let mut writer = FrameWriter::new(&directory, Rectangle::new(0, 0, 4, 4), Style::default(), ImageFormat::Pgm)
+        .unwrap();
+    let mut board = glider();
+    for _ in 0..3 {
+        writer.write_frame(&board).unwrap();
+        board = crate::conway_step(&board, &crate::neighborhood::moore(1), &crate::LifeRule::conway());
+    }
+    assert_eq!(writer.count(), 3);
+    let last = fs::read(directory.join("frame-00002.pgm")).unwrap();
+    assert_eq!(last.len(), b"P5\n16 16\n255\n".len() + 16 * 16);
+    fs::remove_dir_all(&directory).unwrap();
+}
@@ -0,0 +1,282 @@
+//! Rules that decide which cells are alive in the next generation.
+//!
+//! A rule is anything implementing `Rule`, including plain
+//! functions and closures.  `LifeRule` holds the outer-totalistic
+//! rules written in birth/survival notation, like "B3/S23" for
+//! Conway's Life or "B2/S12" for the hex floor in day 24.
+
+use std::collections;
+use std::error;
+use std::fmt;
+use std::str;
+
+/// Decides whether a cell is alive in the next generation.
+pub trait Rule {
+    /// Returns true if a cell should be alive in the next generation,
+    /// given whether it is alive now and how many live neighbors it has.
+    fn is_alive(&self, was_alive: bool, live_neighbors: usize) -> bool;
+}
+
+/// Functions and closures are rules, so a rule can capture state.
+impl<F: Fn(bool, usize) -> bool> Rule for F {
+    fn is_alive(&self, was_alive: bool, live_neighbors: usize) -> bool {
+        self(was_alive, live_neighbors)
+    }
+}
+
+/// An outer-totalistic rule: a dead cell is born when its live
+/// neighbor count is in `birth`, and a live cell survives when its
+/// count is in `survival`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LifeRule {
+    pub(crate) birth: collections::BTreeSet<usize>,
+    pub(crate) survival: collections::BTreeSet<usize>,
+}
+
+impl LifeRule {
+    /// Creates a rule from the neighbor counts that cause birth and survival.
+    /// Panics on a count of 0, which `parse_counts` rejects for the same
+    /// reason: the steppers only look at cells next to a live cell.
+    pub fn new(birth: &[usize], survival: &[usize]) -> LifeRule {
+        assert!(!birth.contains(&0) && !survival.contains(&0), "neighbor count 0 is not supported");
+        LifeRule {
+            birth: birth.iter().cloned().collect(),
+            survival: survival.iter().cloned().collect(),
+        }
+    }
+
+    /// Conway's original rule, "B3/S23".
+    pub fn conway() -> LifeRule {
+        LifeRule::new(&[3], &[2, 3])
+    }
+
+    /// The neighbor counts that cause a dead cell to come alive, in order.
+    pub fn birth(&self) -> impl Iterator<Item = usize> + '_ {
+        self.birth.iter().cloned()
+    }
+
+    /// The neighbor counts that keep a live cell alive, in order.
+    pub fn survival(&self) -> impl Iterator<Item = usize> + '_ {
+        self.survival.iter().cloned()
+    }
+}
+
+impl Rule for LifeRule {
+    fn is_alive(&self, was_alive: bool, live_neighbors: usize) -> bool {
+        if was_alive {
+            self.survival.contains(&live_neighbors)
+        } else {
+            self.birth.contains(&live_neighbors)
+        }
+    }
+}
+
+/// Writes the rule in "B3/S23" notation.  Counts of ten or more
+/// can't be written in this notation, so they will not parse back.
+impl fmt::Display for LifeRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("B")?;
+        for n in self.birth() {
+            write!(f, "{}", n)?;
+        }
+        f.write_str("/S")?;
+        for n in self.survival() {
+            write!(f, "{}", n)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses "B3/S23" notation.  The letters can be either case, and the
+/// parts can be in either order.  The older "23/3" notation, with
+/// survival first and no letters, is also accepted.
+impl str::FromStr for LifeRule {
+    type Err = ParseRuleError;
+
+    fn from_str(s: &str) -> Result<LifeRule, ParseRuleError> {
+        let parts = split_rule(s)?;
+        if parts.len() == 2 && parts.iter().all(|p| p.letter.is_none()) {
+            return Ok(LifeRule {
+                birth: parse_counts('B', parts[1].text)?,
+                survival: parse_counts('S', parts[0].text)?,
+            });
+        }
+        LifeRule::from_parts(parts)
+    }
+}
+
+impl LifeRule {
+    /// Builds a rule from lettered parts, which must be exactly
+    /// one `B` part and one `S` part.
+    pub(crate) fn from_parts(parts: Vec<RulePart<'_>>) -> Result<LifeRule, ParseRuleError> {
+        let mut birth = None;
+        let mut survival = None;
+        for part in parts {
+            let slot = match part.letter {
+                Some('B') => &mut birth,
+                Some('S') => &mut survival,
+                _ => return Err(ParseRuleError::UnknownPart(String::from(part.whole))),
+            };
+            if slot.is_some() {
+                return Err(ParseRuleError::DuplicatePart(part.letter.unwrap()));
+            }
+            *slot = Some(parse_counts(part.letter.unwrap(), part.text)?);
+        }
+        Ok(LifeRule {
+            birth: birth.ok_or(ParseRuleError::MissingPart('B'))?,
+            survival: survival.ok_or(ParseRuleError::MissingPart('S'))?,
+        })
+    }
+}
+
+/// One of the slash-separated parts of a rule string.
+pub(crate) struct RulePart<'a> {
+    /// The whole part, as written.
+    pub whole: &'a str,
+    /// The upper-cased leading letter, if the part starts with one.
+    pub letter: Option<char>,
+    /// The rest of the part after the letter.
+    pub text: &'a str,
+}
+
+/// Splits a rule string on slashes, separating each part's leading letter.
+pub(crate) fn split_rule(s: &str) -> Result<Vec<RulePart<'_>>, ParseRuleError> {
+    let s = s.trim();
+    if s.is_empty() {
+        return Err(ParseRuleError::Empty);
+    }
+    Ok(
+        s.split('/')
+            .map(|whole| {
+                match whole.chars().next() {
+                    Some(c) if c.is_ascii_alphabetic() => RulePart {
+                        whole,
+                        letter: Some(c.to_ascii_uppercase()),
+                        text: &whole[1..],
+                    },
+                    _ => RulePart { whole, letter: None, text: whole },
+                }
+            })
+            .collect()
+    )
+}
+
+/// Parses the digits in one part of a rule, each a neighbor count.
+/// A count of 0 is rejected: the steppers only look at cells next to
+/// a live cell, so B0 and S0 would not do what they say.
+pub(crate) fn parse_counts(part: char, text: &str) -> Result<collections::BTreeSet<usize>, ParseRuleError> {
+    let mut result = collections::BTreeSet::new();
+    for c in text.chars() {
+        let n = c.to_digit(10).ok_or(ParseRuleError::BadCount { part, found: c })?;
+        if n == 0 {
+            return Err(ParseRuleError::ZeroCount(part));
+        }
+        if !result.insert(n as usize) {
+            return Err(ParseRuleError::RepeatedCount { part, count: n as usize });
+        }
+    }
+    Ok(result)
+}
+
+/// The ways a rule string can be malformed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ParseRuleError {
+    /// The rule string was empty.
+    Empty,
+    /// A part of the rule did not start with a known letter.
+    UnknownPart(String),
+    /// The part with this letter appeared more than once.
+    DuplicatePart(char),
+    /// The part with this letter is required, but was not there.
+    MissingPart(char),
+    /// A part contained something other than a digit.
+    BadCount { part: char, found: char },
+    /// A part listed the same neighbor count twice.
+    RepeatedCount { part: char, count: usize },
+    /// A part listed a count of 0, which isn't supported.
+    ZeroCount(char),
+    /// The number of states in a Generations rule was not a
+    /// number from 2 to 256.
+    BadStateCount(String),
+}
+
+impl fmt::Display for ParseRuleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ParseRuleError::Empty =>
+                write!(f, "rule is empty"),
+            ParseRuleError::UnknownPart(text) =>
+                write!(f, "unrecognized rule part {:?}", text),
+            ParseRuleError::DuplicatePart(letter) =>
+                write!(f, "rule has more than one {} part", letter),
+            ParseRuleError::MissingPart(letter) =>
+                write!(f, "rule has no {} part", letter),
+            ParseRuleError::BadCount { part, found } =>
+                write!(f, "{:?} in {} part is not a neighbor count", found, part),
+            ParseRuleError::RepeatedCount { part, count } =>
+                write!(f, "neighbor count {} appears twice in {} part", count, part),
+            ParseRuleError::ZeroCount(part) =>
+                write!(f, "neighbor count 0 in {} part is not supported", part),
+            ParseRuleError::BadStateCount(text) =>
+                write!(f, "state count {:?} is not a number from 2 to 256", text),
+        }
+    }
+}
+
+impl error::Error for ParseRuleError {}
+
+#[test]
+fn test_parse_rule() {
+    assert_eq!("B3/S23".parse(), Ok(LifeRule::conway()));
+    assert_eq!("s23/b3".parse(), Ok(LifeRule::conway()));
+    assert_eq!("23/3".parse(), Ok(LifeRule::conway()));
+    assert_eq!("B2/S12".parse(), Ok(LifeRule::new(&[2], &[1, 2])));
+    assert_eq!("B36/S".parse(), Ok(LifeRule::new(&[3, 6], &[])));
+}
+
+#[test]
+fn test_parse_rule_errors() {
+    assert_eq!("".parse::<LifeRule>(), Err(ParseRuleError::Empty));
+    assert_eq!("B3".parse::<LifeRule>(), Err(ParseRuleError::MissingPart('S')));
+    assert_eq!("B3/S23/B4".parse::<LifeRule>(), Err(ParseRuleError::DuplicatePart('B')));
+    assert_eq!("B3/X23".parse::<LifeRule>(), Err(ParseRuleError::UnknownPart(String::from("X23"))));
+    assert_eq!(
+        "B3/S2x".parse::<LifeRule>(),
+        Err(ParseRuleError::BadCount { part: 'S', found: 'x' })
+    );
+    assert_eq!(
+        "B33/S23".parse::<LifeRule>(),
+        Err(ParseRuleError::RepeatedCount { part: 'B', count: 3 })
+    );
+    assert_eq!("B0/S23".parse::<LifeRule>(), Err(ParseRuleError::ZeroCount('B')));
+    assert_eq!("B3/S0".parse::<LifeRule>(), Err(ParseRuleError::ZeroCount('S')));
+    assert_eq!(
+        "B3/S023".parse::<LifeRule>().unwrap_err().to_string(),
+        "neighbor count 0 in S part is not supported"
+    );
+    assert_eq!(
+        "B3/S2x".parse::<LifeRule>().unwrap_err().to_string(),
+        "'x' in S part is not a neighbor count"
+    );
+}
+
+#[test]
+#[should_panic(expected = "neighbor count 0 is not supported")]
+fn test_new_rejects_zero() {
+    LifeRule::new(&[0, 3], &[2, 3]);
+}
+
+#[test]
+fn test_display_rule() {
+    assert_eq!(LifeRule::conway().to_string(), "B3/S23");
+    assert_eq!("s12/b2".parse::<LifeRule>().unwrap().to_string(), "B2/S12");
+}
+
+#[test]
+fn test_rule_is_alive() {
+    let rule = LifeRule::conway();
+    assert!(rule.is_alive(false, 3));
+    assert!(!rule.is_alive(false, 2));
+    assert!(rule.is_alive(true, 2));
+    assert!(!rule.is_alive(true, 4));
+}
@@ -0,0 +1,270 @@
+//! Running Life for many generations, and keeping statistics.
+//!
+//! A `Simulation` owns the board, the neighborhood, and the rule.
+//! After every generation, including generation 0, it asks each of
+//! its observers for some numbers, and keeps them as a row of a
+//! table that can be written out as CSV, ready to load into a
+//! spreadsheet or plotting tool.
+
+use std::collections;
+use std::hash;
+use std::io;
+use std::ops;
+use std::time;
+
+use crate::incremental::{Diff, Incremental};
+use crate::point::Planar;
+use crate::rule::Rule;
+
+/// What observers get to see after each generation.
+pub struct Generation<'a, T: Eq + hash::Hash> {
+    /// The generation number, starting at 0.
+    pub number: usize,
+    pub board: &'a collections::HashSet<T>,
+    /// What changed in the step that made this generation.  Empty
+    /// for generation 0.
+    pub diff: &'a Diff<T>,
+    /// How long the step took.  Zero for generation 0.
+    pub elapsed: time::Duration,
+}
+
+/// Something that reports statistics about each generation.
+pub trait Observer<T: Eq + hash::Hash> {
+    /// The names of the columns this observer fills in.
+    fn columns(&self) -> Vec<String>;
+
+    /// Returns one value for each column.
+    fn observe(&mut self, generation: &Generation<T>) -> Vec<String>;
+}
+
+/// The number of live cells.
+pub struct Population;
+
+impl<T: Eq + hash::Hash> Observer<T> for Population {
+    fn columns(&self) -> Vec<String> {
+        vec![String::from("population")]
+    }
+
+    fn observe(&mut self, generation: &Generation<T>) -> Vec<String> {
+        vec![generation.board.len().to_string()]
+    }
+}
+
+/// The number of cells born and the number that died.
+pub struct BirthsAndDeaths;
+
+impl<T: Copy + Eq + hash::Hash> Observer<T> for BirthsAndDeaths {
+    fn columns(&self) -> Vec<String> {
+        vec![String::from("births"), String::from("deaths")]
+    }
+
+    fn observe(&mut self, generation: &Generation<T>) -> Vec<String> {
+        vec![generation.diff.births.len().to_string(), generation.diff.deaths.len().to_string()]
+    }
+}
+
+/// The smallest and largest x and y of the live cells.  All four
+/// are blank when there are no live cells.
+pub struct BoundingBox;
+
+impl<T: Planar + Eq + hash::Hash> Observer<T> for BoundingBox {
+    fn columns(&self) -> Vec<String> {
+        ["min_x", "min_y", "max_x", "max_y"].iter().map(|&s| String::from(s)).collect()
+    }
+
+    fn observe(&mut self, generation: &Generation<T>) -> Vec<String> {
+        let board = generation.board;
+        if board.is_empty() {
+            return vec![String::new(); 4];
+        }
+        vec![
+            board.iter().map(|c| c.x()).min().unwrap().to_string(),
+            board.iter().map(|c| c.y()).min().unwrap().to_string(),
+            board.iter().map(|c| c.x()).max().unwrap().to_string(),
+            board.iter().map(|c| c.y()).max().unwrap().to_string(),
+        ]
+    }
+}
+
+/// How long each step took, in microseconds.
+pub struct ElapsedTime;
+
+impl<T: Eq + hash::Hash> Observer<T> for ElapsedTime {
+    fn columns(&self) -> Vec<String> {
+        vec![String::from("elapsed_us")]
+    }
+
+    fn observe(&mut self, generation: &Generation<T>) -> Vec<String> {
+        vec![generation.elapsed.as_micros().to_string()]
+    }
+}
+
+/// A board being run forward in Life, with observers watching.
+pub struct Simulation<T, R> {
+    board: Incremental<T>,
+    rule: R,
+    generation: usize,
+    observers: Vec<Box<dyn Observer<T>>>,
+    /// One row for each generation so far, with the generation number
+    /// followed by the values from each observer.
+    rows: Vec<Vec<String>>,
+}
+
+impl<T, R> Simulation<T, R>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          R: Rule
+{
+    /// Creates a simulation, and records generation 0.
+    pub fn new(
+        board: collections::HashSet<T>,
+        neighbor_deltas: &[T],
+        rule: R,
+        observers: Vec<Box<dyn Observer<T>>>
+    ) -> Simulation<T, R> {
+        let mut result = Simulation {
+            board: Incremental::new(board, neighbor_deltas),
+            rule,
+            generation: 0,
+            observers,
+            rows: Vec::new(),
+        };
+        let no_change = Diff { births: collections::HashSet::new(), deaths: collections::HashSet::new() };
+        result.record(&no_change, time::Duration::default());
+        result
+    }
+
+    /// The live cells.
+    pub fn board(&self) -> &collections::HashSet<T> {
+        self.board.cells()
+    }
+
+    /// The number of the current generation.
+    pub fn generation(&self) -> usize {
+        self.generation
+    }
+
+    /// Runs one step, and records what the observers see.
+    pub fn step(&mut self) -> Diff<T> {
+        let start = time::Instant::now();
+        let diff = self.board.step(&self.rule);
+        let elapsed = start.elapsed();
+        self.generation += 1;
+        self.record(&diff, elapsed);
+        diff
+    }
+
+    /// Returns an iterator that runs one step each time it's called,
+    /// and returns what changed.  It never ends, so use `take`.
+    pub fn steps(&mut self) -> Steps<'_, T, R> {
+        Steps { simulation: self }
+    }
+
+    fn record(&mut self, diff: &Diff<T>, elapsed: time::Duration) {
+        let generation = Generation { number: self.generation, board: self.board.cells(), diff, elapsed };
+        let mut row = vec![self.generation.to_string()];
+        for observer in self.observers.iter_mut() {
+            row.extend(observer.observe(&generation));
+        }
+        self.rows.push(row);
+    }
+
+    /// The names of the columns, starting with "generation".
+    pub fn columns(&self) -> Vec<String> {
+        let mut result = vec![String::from("generation")];
+        for observer in self.observers.iter() {
+            result.extend(observer.columns());
+        }
+        result
+    }
+
+    /// The values recorded so far, one row per generation.
+    pub fn rows(&self) -> &[Vec<String>] {
+        &self.rows
+    }
+
+    /// Writes the columns and rows as CSV.
+    pub fn write_csv<W: io::Write>(&self, out: &mut W) -> io::Result<()> {
+        writeln!(out, "{}", csv_line(&self.columns()))?;
+        for row in self.rows.iter() {
+            writeln!(out, "{}", csv_line(row))?;
+        }
+        Ok(())
+    }
+}
+
+/// Joins the values with commas, quoting any that need it.
+fn csv_line(values: &[String]) -> String {
+    values.iter()
+        .map(|v| {
+            if v.contains([',', '"', '\n']) {
+                format!("\"{}\"", v.replace('"', "\"\""))
+            } else {
+                v.clone()
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// The iterator returned by `Simulation::steps`.
+pub struct Steps<'a, T, R> {
+    simulation: &'a mut Simulation<T, R>,
+}
+
+impl<'a, T, R> Iterator for Steps<'a, T, R>
+    where T: ops::Add<Output = T> + Copy + Eq + hash::Hash,
+          R: Rule
+{
+    type Item = Diff<T>;
+
+    fn next(&mut self) -> Option<Diff<T>> {
+        Some(self.simulation.step())
+    }
+}
+
+#[cfg(test)]
+use crate::point::{point, Point};
+
+#[test]
+fn test_glider_statistics() {
+    let glider: collections::HashSet<Point> =
+        [point(1, 0), point(2, 1), point(0, 2), point(1, 2), point(2, 2)].iter().cloned().collect();
+    let observers: Vec<Box<dyn Observer<Point>>> =
+        vec![Box::new(Population), Box::new(BirthsAndDeaths), Box::new(BoundingBox)];
+    let mut simulation =
+        Simulation::new(glider, &crate::neighborhood::moore(1), crate::LifeRule::conway(), observers);
+    let changes: Vec<usize> = simulation.steps().take(4).map(|diff| diff.births.len()).collect();
+    assert_eq!(changes, vec![2, 2, 2, 2]);
+    assert_eq!(simulation.generation(), 4);
+    assert_eq!(simulation.board().len(), 5);
+
+    let mut csv = Vec::new();
+    simulation.write_csv(&mut csv).unwrap();
+    assert_eq!(
+        String::from_utf8(csv).unwrap(),
+        "generation,population,births,deaths,min_x,min_y,max_x,max_y\n\
+         0,5,0,0,0,0,2,2\n\
+         1,5,2,2,0,1,2,3\n\
+         2,5,2,2,0,1,2,3\n\
+         3,5,2,2,1,1,3,3\n\
+         4,5,2,2,1,1,3,3\n"
+    );
+}
+
+#[test]
+fn test_elapsed_and_empty_board() {
+    let observers: Vec<Box<dyn Observer<Point>>> = vec![Box::new(BoundingBox), Box::new(ElapsedTime)];
+    let domino: collections::HashSet<Point> = [point(0, 0), point(1, 0)].iter().cloned().collect();
+    let mut simulation =
+        Simulation::new(domino, &crate::neighborhood::moore(1), crate::LifeRule::conway(), observers);
+    simulation.step();
+    assert_eq!(simulation.columns(), vec!["generation", "min_x", "min_y", "max_x", "max_y", "elapsed_us"]);
+    assert_eq!(simulation.rows()[0], vec!["0", "0", "0", "1", "0", "0"]);
+    assert_eq!(simulation.rows()[1][..5], ["1", "", "", "", ""]);
+}
+
+#[test]
+fn test_csv_line() {
+    let values: Vec<String> = ["a", "b,c", "say \"hi\""].iter().map(|&s| String::from(s)).collect();
+    assert_eq!(csv_line(&values), "a,\"b,c\",\"say \"\"hi\"\"\"");
+}
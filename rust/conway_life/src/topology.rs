@@ -0,0 +1,99 @@
+//! The shape of the universe a board lives in.
+//!
+//! `conway_step` assumes an infinite universe, where the neighbors of
+//! a cell are just the cell plus each offset.  A `Topology` gets to
+//! look at each neighbor computed that way, and either rename it (for
+//! boards that wrap around) or reject it (for boards with walls).
+
+use crate::point::Planar;
+
+/// Decides which cell, if any, a computed neighbor refers to.
+pub trait Topology<T> {
+    /// Returns the name of the cell on the board, or None if the
+    /// cell is off the edge of the board.
+    fn normalize(&self, cell: T) -> Option<T>;
+}
+
+/// The infinite universe, where every cell is on the board.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Unbounded;
+
+impl<T> Topology<T> for Unbounded {
+    fn normalize(&self, cell: T) -> Option<T> {
+        Some(cell)
+    }
+}
+
+/// A rectangle of cells on a 2-D board: x from `min_x` up to
+/// (but not including) `min_x + width`, and the same for y.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Rectangle {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub width: i32,
+    pub height: i32,
+}
+
+impl Rectangle {
+    /// Creates a rectangle.  Panics if it would be empty.
+    pub fn new(min_x: i32, min_y: i32, width: i32, height: i32) -> Rectangle {
+        assert!(0 < width && 0 < height, "empty rectangle: {} x {}", width, height);
+        Rectangle { min_x, min_y, width, height }
+    }
+
+    /// Does the rectangle contain the cell?
+    pub fn contains<T: Planar>(&self, cell: &T) -> bool {
+        (self.min_x..self.min_x + self.width).contains(&cell.x()) &&
+            (self.min_y..self.min_y + self.height).contains(&cell.y())
+    }
+}
+
+/// A finite 2-D board with walls.  Cells past the walls are
+/// always dead.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Bounded(pub Rectangle);
+
+impl<T: Planar> Topology<T> for Bounded {
+    fn normalize(&self, cell: T) -> Option<T> {
+        if self.0.contains(&cell) { Some(cell) } else { None }
+    }
+}
+
+/// A finite 2-D board where the right edge neighbors the left edge,
+/// and the top edge neighbors the bottom.
+///
+/// With axial hex coordinates, the rectangle is a parallelogram of
+/// hexes, which also tiles the plane, so this works for hex boards too.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Torus(pub Rectangle);
+
+impl<T: Planar> Topology<T> for Torus {
+    fn normalize(&self, cell: T) -> Option<T> {
+        let r = &self.0;
+        Some(T::from_xy(
+            r.min_x + (cell.x() - r.min_x).rem_euclid(r.width),
+            r.min_y + (cell.y() - r.min_y).rem_euclid(r.height),
+        ))
+    }
+}
+
+#[cfg(test)]
+use crate::point::point;
+
+#[test]
+fn test_bounded() {
+    let bounded = Bounded(Rectangle::new(-1, 0, 3, 2));
+    assert_eq!(bounded.normalize(point(-1, 0)), Some(point(-1, 0)));
+    assert_eq!(bounded.normalize(point(1, 1)), Some(point(1, 1)));
+    assert_eq!(bounded.normalize(point(2, 1)), None);
+    assert_eq!(bounded.normalize(point(0, -1)), None);
+}
+
+#[test]
+fn test_torus() {
+    let torus = Torus(Rectangle::new(-1, 0, 3, 2));
+    assert_eq!(torus.normalize(point(0, 1)), Some(point(0, 1)));
+    assert_eq!(torus.normalize(point(2, 1)), Some(point(-1, 1)));
+    assert_eq!(torus.normalize(point(-2, -1)), Some(point(1, 1)));
+    assert_eq!(torus.normalize(point(8, 7)), Some(point(-1, 1)));
+}
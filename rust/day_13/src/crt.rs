@@ -0,0 +1,172 @@
+//! The Chinese remainder theorem, for moduli that aren't coprime.
+//!
+//! A system of congruences x ≡ a₁ (mod m₁), x ≡ a₂ (mod m₂), ... is
+//! solved by combining them two at a time.  Two congruences have a
+//! common solution exactly when a₁ ≡ a₂ (mod gcd(m₁, m₂)), and then
+//! the solutions are one congruence modulo lcm(m₁, m₂).  The extended
+//! Euclidean algorithm finds it.
+
+use std::error;
+use std::fmt;
+
+/// x ≡ remainder (mod modulus).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Congruence {
+    pub remainder: u64,
+    pub modulus: u64,
+}
+
+impl Congruence {
+    /// A congruence with the remainder reduced to be less than the
+    /// modulus, which must not be 0.
+    pub fn new(remainder: u64, modulus: u64) -> Congruence {
+        assert!(modulus != 0, "a modulus can't be 0");
+        Congruence { remainder: remainder % modulus, modulus }
+    }
+}
+
+impl fmt::Display for Congruence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "x ≡ {} (mod {})", self.remainder, self.modulus)
+    }
+}
+
+/// Why a system of congruences couldn't be solved.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Error {
+    /// No number satisfies both congruences.  From `solve`, the first
+    /// one is the combination of all of the congruences before the
+    /// second; from `check`, it is one of them.
+    Inconsistent(Congruence, Congruence),
+    /// The period of the solutions doesn't fit in a u64.
+    Overflow,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Inconsistent(a, b) => write!(f, "no number has both {} and {}", a, b),
+            Error::Overflow => write!(f, "the period of the solutions is more than 2^64"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Returns (g, p, q) where g = gcd(a, b) and p * a + q * b = g.
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        (a, 1, 0)
+    } else {
+        let (g, p, q) = extended_gcd(b, a % b);
+        (g, q, p - (a / b) * q)
+    }
+}
+
+#[test]
+fn test_extended_gcd() {
+    assert_eq!(extended_gcd(240, 46), (2, -9, 47));
+    assert_eq!(extended_gcd(7, 0), (7, 1, 0));
+    let (g, p, q) = extended_gcd(35, 21);
+    assert_eq!((g, 35 * p + 21 * q), (7, 7));
+}
+
+fn gcd(a: u128, b: u128) -> u128 {
+    if b == 0 { a } else { gcd(b, a % b) }
+}
+
+/// The congruence that holds exactly when both of the given ones do.
+pub fn combine(a: Congruence, b: Congruence) -> Result<Congruence, Error> {
+    let (g, p, _) = extended_gcd(a.modulus as i128, b.modulus as i128);
+    let g = g as u128;
+    let (a_rem, a_mod, b_rem, b_mod) = (a.remainder as u128, a.modulus as u128, b.remainder as u128, b.modulus as u128);
+    let difference = (b_rem + b_mod - a_rem % b_mod) % b_mod;
+    if difference % g != 0 {
+        return Err(Error::Inconsistent(a, b));
+    }
+    let b_over_g = b_mod / g;
+    let lcm = a_mod * b_over_g;
+    if lcm > u64::MAX as u128 {
+        return Err(Error::Overflow);
+    }
+    // p * a.modulus ≡ g (mod b.modulus), so adding a.modulus times
+    // k = (difference / g) * p to a.remainder gets to b.remainder.
+    let p = p.rem_euclid(b_over_g as i128) as u128;
+    let k = (difference / g % b_over_g) * p % b_over_g;
+    Ok(Congruence { remainder: ((a_rem + a_mod * k) % lcm) as u64, modulus: lcm as u64 })
+}
+
+/// The solutions to a system of congruences: every number that is
+/// `smallest` plus a multiple of `period`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Solution {
+    /// The smallest non-negative solution.
+    pub smallest: u64,
+    /// The least common multiple of the moduli.
+    pub period: u64,
+}
+
+/// Solves all of the congruences at once.  With no congruences, every
+/// number is a solution.
+pub fn solve(congruences: &[Congruence]) -> Result<Solution, Error> {
+    let mut combined = Congruence::new(0, 1);
+    for &c in congruences {
+        combined = combine(combined, c)?;
+    }
+    Ok(Solution { smallest: combined.remainder, period: combined.modulus })
+}
+
+/// Checks that the congruences have a solution, without finding it.
+/// They do exactly when every two of them agree modulo the gcd of
+/// their moduli, and the period of the solutions fits in a u64.
+pub fn check(congruences: &[Congruence]) -> Result<(), Error> {
+    let mut period: u128 = 1;
+    for (i, &b) in congruences.iter().enumerate() {
+        for &a in congruences[..i].iter() {
+            let g = gcd(a.modulus as u128, b.modulus as u128) as u64;
+            if a.remainder % g != b.remainder % g {
+                return Err(Error::Inconsistent(a, b));
+            }
+        }
+        period = period / gcd(period, b.modulus as u128) * b.modulus as u128;
+        if period > u64::MAX as u128 {
+            return Err(Error::Overflow);
+        }
+    }
+    Ok(())
+}
+
+#[test]
+fn test_check() {
+    let c = Congruence::new;
+    assert_eq!(check(&[c(2, 3), c(3, 5), c(2, 7)]), Ok(()));
+    assert_eq!(check(&[c(2, 6), c(0, 4)]), Ok(()));
+    assert_eq!(check(&[c(1, 3), c(1, 6), c(0, 4)]), Err(Error::Inconsistent(c(1, 6), c(0, 4))));
+    assert_eq!(check(&[c(1, u64::MAX), c(0, u64::MAX - 1)]), Err(Error::Overflow));
+    // Each pair fits, but all three don't.
+    let (p, q, r) = (4294967291, 4294967279, 65537);
+    assert_eq!(check(&[c(1, p), c(1, q)]), Ok(()));
+    assert_eq!(check(&[c(1, p), c(1, q), c(1, r)]), Err(Error::Overflow));
+}
+
+#[test]
+fn test_solve() {
+    let c = Congruence::new;
+    assert_eq!(solve(&[c(2, 3), c(3, 5), c(2, 7)]), Ok(Solution { smallest: 23, period: 105 }));
+    assert_eq!(solve(&[]), Ok(Solution { smallest: 0, period: 1 }));
+    // 6 and 4 aren't coprime, but 2 mod 6 and 0 mod 4 agree mod 2.
+    assert_eq!(solve(&[c(2, 6), c(0, 4)]), Ok(Solution { smallest: 8, period: 12 }));
+    assert_eq!(solve(&[c(9, 6), c(3, 9)]), Ok(Solution { smallest: 3, period: 18 }));
+    assert_eq!(solve(&[c(1, 6), c(0, 4)]), Err(Error::Inconsistent(c(1, 6), c(0, 4))));
+    assert_eq!(
+        solve(&[c(1, 6), c(0, 4)]).unwrap_err().to_string(),
+        "no number has both x ≡ 1 (mod 6) and x ≡ 0 (mod 4)"
+    );
+    assert_eq!(solve(&[c(1, u64::MAX), c(0, u64::MAX - 1)]), Err(Error::Overflow));
+
+    // Two primes just under 2^32, so the period is just under 2^64.
+    let (p, q) = (4294967291, 4294967279);
+    let solution = solve(&[c(p - 1, p), c(12345, q)]).unwrap();
+    assert_eq!(solution.period, p * q);
+    assert_eq!((solution.smallest % p, solution.smallest % q), (p - 1, 12345));
+}
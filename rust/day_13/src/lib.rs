@@ -0,0 +1,148 @@
+
+
+//! Day 13: Shuttle Search.
+
+use std::error;
+
+use aoc_common::{ErrorKind, InputError, Line};
+use aoc_solution::{Answer, Solution};
+
+pub mod crt;
+
+pub struct Input {
+    earliest: u64,
+    buses: Vec<Bus>,
+}
+
+/// A bus that is in service, and where it is in the list of buses.
+struct Bus {
+    id: u64,
+    index: u64,
+}
+
+/// Parses a comma-separated list of buses, where "x" is a bus that
+/// is out of service.
+fn parse_buses(line: &Line) -> Result<Vec<Bus>, InputError> {
+    let mut result = Vec::new();
+    for (index, bus) in line.text.trim().split(",").enumerate() {
+        if bus != "x" {
+            let id = aoc_common::parse_number::<u64>(bus, line.number)?;
+            if id == 0 {
+                return Err(line.invalid("there is no bus 0"));
+            }
+            result.push(Bus { id, index: index as u64 });
+        }
+    }
+    if result.is_empty() {
+        return Err(line.error(ErrorKind::Missing(String::from("buses in service"))));
+    }
+    Ok(result)
+}
+
+/// Parses the input file: the earliest time on the first line, and
+/// the bus notes on the second.  Buses that can never leave at the
+/// times part 2 asks for are an error in the input.
+fn parse_input(text: &str) -> Result<Input, InputError> {
+    let mut lines = aoc_common::lines(text);
+    let last_line = text.lines().count();
+    let missing = |what: &str| InputError::new(last_line, ErrorKind::Missing(String::from(what)));
+    let earliest = lines.next().ok_or_else(|| missing("earliest time"))?.parse::<u64>()?;
+    let buses_line = lines.next().ok_or_else(|| missing("bus notes"))?;
+    let buses = parse_buses(&buses_line)?;
+    if let Err(e) = crt::check(&congruences(&buses)) {
+        return Err(buses_line.invalid(format!("the buses never line up: {}", e)));
+    }
+    Ok(Input { earliest, buses })
+}
+
+#[cfg(test)]
+fn buses(note: &str) -> Vec<Bus> {
+    parse_buses(&Line { number: 1, text: note }).unwrap()
+}
+
+#[test]
+fn test_parse_input() {
+    let error = |text: &str| parse_input(text).err().unwrap().to_string();
+    assert_eq!(error("939\n7,0,x\n"), "line 2: there is no bus 0");
+    assert_eq!(error("939\nx,x\n"), "line 2: missing buses in service");
+    assert_eq!(error("939\n7,99999999999999999999\n"), "line 2: expected a number, found \"99999999999999999999\"");
+    assert_eq!(
+        error("939\n4,6\n"),
+        "line 2: the buses never line up: no number has both x ≡ 0 (mod 4) and x ≡ 5 (mod 6)"
+    );
+    assert_eq!(error("939\n"), "line 1: missing bus notes");
+    assert_eq!(error(""), "missing earliest time");
+}
+
+fn next_arrival_time(earliest: u64, bus_id: u64) -> u64 {
+    earliest.div_ceil(bus_id) * bus_id
+}
+
+/// The id of the first bus to leave at or after the earliest time,
+/// times how long it is to wait for it.
+fn day13_part1(input: &Input) -> u64 {
+    let (departure, bus_id) =
+        input.buses.iter()
+            .map(|bus| (next_arrival_time(input.earliest, bus.id), bus.id))
+            .min()
+            .expect("parse_buses checks there is a bus in service");
+    bus_id * (departure - input.earliest)
+}
+
+#[test]
+fn test_day13_part1() {
+    let input = Input { earliest: 939, buses: buses("7,13,x,x,59,x,31,19") };
+    assert_eq!(day13_part1(&input), 295);
+    let big = Input { earliest: 10000000000, buses: buses("3000000000") };
+    assert_eq!(day13_part1(&big), 3000000000 * 2000000000);
+}
+
+/// The times t when each bus leaves at t plus its index.  Bus `id`
+/// leaves at multiples of `id`, so t ≡ -index (mod id).
+fn congruences(buses: &[Bus]) -> Vec<crt::Congruence> {
+    buses.iter()
+        .map(|bus| crt::Congruence::new(bus.id - bus.index % bus.id, bus.id))
+        .collect()
+}
+
+/// Finds the earliest time t when each bus leaves at t plus its index.
+fn day13_part2(buses: &[Bus]) -> Result<u64, crt::Error> {
+    Ok(crt::solve(&congruences(buses))?.smallest)
+}
+
+#[test]
+fn test_day13_part2() {
+    assert_eq!(Ok(14), day13_part2(&buses("7,5")));
+    assert_eq!(Ok(3417), day13_part2(&buses("17,x,13,19")));
+    assert_eq!(Ok(754018), day13_part2(&buses("67,7,59,61")));
+    assert_eq!(Ok(779210), day13_part2(&buses("67,x,7,59,61")));
+    assert_eq!(Ok(1261476), day13_part2(&buses("67,7,x,59,61")));
+    assert_eq!(Ok(1202161486), day13_part2(&buses("1789,37,47,1889")));
+    // Buses 4 and 6 both leave at even times, so they can't leave one
+    // minute apart.
+    assert_eq!(
+        day13_part2(&buses("4,6")).unwrap_err().to_string(),
+        "no number has both x ≡ 0 (mod 4) and x ≡ 5 (mod 6)"
+    );
+    assert_eq!(Ok(4), day13_part2(&buses("4,x,6")));
+}
+
+pub struct Day13;
+
+impl Solution for Day13 {
+    type Input = Input;
+    const DAY: u32 = 13;
+    const TITLE: &'static str = "Shuttle Search";
+
+    fn parse(&self, text: &str) -> Result<Input, Box<dyn error::Error>> {
+        Ok(parse_input(text)?)
+    }
+
+    fn part1(&self, input: &Input) -> Answer {
+        Answer::from(day13_part1(input))
+    }
+
+    fn part2(&self, input: &Input) -> Answer {
+        Answer::from(day13_part2(&input.buses).expect("parse_input checks the buses line up"))
+    }
+}
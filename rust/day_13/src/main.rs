@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_13::Day13, "input/day13-input.txt");
+}
@@ -0,0 +1,228 @@
+//! Day 14: Docking Data.
+
+use std::collections::HashMap;
+use std::error;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+use aoc_common::{ErrorKind, InputError, Line};
+use aoc_solution::{Answer, Solution};
+
+type Memory = HashMap<u64, u64>;
+
+/// Sets the bit at 2^`i` in the number `n`.
+fn set_bit(n: u64, i: usize) -> u64 {
+    n | (1 << i)
+}
+
+fn clear_bit(n: u64, i: usize) -> u64 {
+    n & !(1 << i)
+}
+
+/// Applies a bitmask to a number, following the rules int Part 1
+fn apply_mask_part1(n: u64, mask: &str) -> u64 {
+    let bytes = mask.as_bytes();
+    let mut result = n;
+    for i in 0usize..36 {
+        let c = bytes[35 - i];
+        if c == b'0' {
+            result = clear_bit(result, i)
+        } else if c == b'1' {
+            result = set_bit(result, i)
+        } else if c != b'X' {
+            panic!();
+        }
+    };
+    result
+}
+
+// Returns a list of the results of applying the mask starting at
+// bit index `index`.  All of the previous bits have already been
+// processed and the results are in `n`.
+fn apply_mask_part2_helper(n: u64, mask: &str, index: usize, result: & mut Vec<u64>) {
+    if index == 36 {
+        result.push(n)
+    } else {
+        let c = mask.as_bytes()[35 - index];
+        if c == b'0' {
+            apply_mask_part2_helper(n, mask, index + 1, result)
+        } else if c == b'1' {
+            apply_mask_part2_helper(set_bit(n, index), mask, index + 1, result)
+        } else if c == b'X' {
+            apply_mask_part2_helper(clear_bit(n, index), mask, index + 1, result);
+            apply_mask_part2_helper(set_bit(n, index), mask, index + 1, result)
+        } else {
+            panic!()
+        }
+
+    }
+}
+
+/// Applies a bitmask to a number, following the rules int Part 1
+fn apply_mask_part2(n: u64, mask: &str) -> Vec<u64> {
+    let mut result = Vec::new();
+    apply_mask_part2_helper(n, mask, 0, &mut result);
+    result
+}
+
+/// One line from the input file
+#[derive(Debug)]
+#[derive(PartialEq)]
+pub enum InputLine {
+    Mask(String),
+    Store{addr: u64, value: u64}
+}
+
+/// Parses one line from the input file
+fn parse_input_line(line: &Line) -> Result<InputLine, InputError> {
+    lazy_static! {
+        static ref STORE: Regex = Regex::new(r"^mem\[([0-9]+)\] *= *([0-9]+)$").expect("store regex");
+    }
+    if let Some(mask) = line.text.strip_prefix("mask = ") {
+        if mask.len() != 36 {
+            return Err(line.invalid(format!("a mask has 36 bits, not {}", mask.len())));
+        }
+        if let Some(c) = mask.chars().find(|c| ! "01X".contains(*c)) {
+            return Err(line.error(ErrorKind::UnexpectedChar(c)));
+        }
+        Ok(InputLine::Mask(String::from(mask)))
+    } 
+    else if let Some(captures) = STORE.captures(line.text) {
+        Ok(
+            InputLine::Store{
+                addr: aoc_common::parse_number(&captures[1], line.number)?,
+                value: aoc_common::parse_number(&captures[2], line.number)?,
+            }
+        )
+    } 
+    else {
+        Err(line.invalid(format!("unrecognized input line: {:?}", line.text)))
+    }
+}
+
+/// Parses all of the lines of the input file.  A value can't be
+/// stored until there is a mask to apply to it.
+fn parse_input(text: &str) -> Result<Vec<InputLine>, InputError> {
+    let mut result = Vec::new();
+    let mut seen_mask = false;
+    for line in aoc_common::lines(text) {
+        let input_line = parse_input_line(&line)?;
+        match input_line {
+            InputLine::Mask(_) => seen_mask = true,
+            InputLine::Store{..} if ! seen_mask => {
+                return Err(line.invalid("a value is stored before the first mask"));
+            },
+            InputLine::Store{..} => {},
+        }
+        result.push(input_line);
+    }
+    Ok(result)
+}
+
+/// Runs the program, and returns the resulting memory.
+fn process_input_part1(program: &[InputLine]) -> Memory {
+    let mut mask: Option<&str> = Option::None;
+    let mut memory: Memory = HashMap::new();
+    for line in program {
+        match line {
+            InputLine::Mask(m) => {
+                mask = Option::Some(m);
+            },
+            InputLine::Store{addr, value} => {
+                memory.insert(*addr, apply_mask_part1(*value, mask.expect("parse_input checks there is a mask first")));
+            }
+        }
+    }
+    memory
+}
+
+/// Runs the program, following the rules in Part 2, and returns the
+/// sum of the values in memory.
+fn process_input_part2(program: &[InputLine]) -> u64 {
+    let mut mask: Option<&str> = Option::None;
+    let mut memory: Memory = HashMap::new();
+    for line in program {
+        match line {
+            InputLine::Mask(m) => {
+                mask = Option::Some(m);
+            },
+            InputLine::Store{addr, value} => {
+                let addresses = apply_mask_part2(*addr, mask.expect("parse_input checks there is a mask first"));
+                for addr in addresses {
+                    memory.insert(addr, *value);
+                }
+            }
+        }
+    }
+    memory.values().sum()
+}
+
+#[test]
+fn test_apply_mask_part1() {
+    assert_eq!(
+        apply_mask_part1(
+            0b000000000000000000000000000000001011, 
+            "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"
+        ),
+        0b000000000000000000000000000001001001
+    );
+    assert_eq!(
+        apply_mask_part1(
+            0b000000000000000000000000000001100101, 
+            "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"
+        ),
+        0b000000000000000000000000000001100101
+    );
+    assert_eq!(
+        apply_mask_part1(
+            0b000000000000000000000000000000000000, 
+            "XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"
+        ),
+        0b000000000000000000000000000001000000
+    );
+    assert_eq!(
+        InputLine::Store{addr:45, value:12345},
+        parse_input_line(&Line { number: 1, text: "mem[45] = 12345" }).unwrap()
+    );
+}
+
+#[test]
+fn test_process_input_part2() {
+    let text = aoc_common::read_file("part2-example.txt").unwrap();
+    assert_eq!(208, process_input_part2(&parse_input(&text).unwrap()));
+}
+
+#[test]
+fn test_parse_errors() {
+    let error = |text: &str| parse_input(text).unwrap_err().to_string();
+    assert_eq!(error("mask = 1X0\n"), "line 1: a mask has 36 bits, not 3");
+    assert_eq!(
+        error("mask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X\nmem[1] = 2\nmask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0Y"),
+        "line 3: unexpected character 'Y'"
+    );
+    assert_eq!(error("\nmem[1] = two"), "line 2: unrecognized input line: \"mem[1] = two\"");
+    assert_eq!(
+        error("\nmem[1] = 2\nmask = XXXXXXXXXXXXXXXXXXXXXXXXXXXXX1XXXX0X"),
+        "line 2: a value is stored before the first mask"
+    );
+}
+
+pub struct Day14;
+
+impl Solution for Day14 {
+    type Input = Vec<InputLine>;
+    const DAY: u32 = 14;
+    const TITLE: &'static str = "Docking Data";
+
+    fn parse(&self, text: &str) -> Result<Vec<InputLine>, Box<dyn error::Error>> {
+        Ok(parse_input(text)?)
+    }
+
+    fn part1(&self, input: &Vec<InputLine>) -> Answer {
+        Answer::from(process_input_part1(input).values().sum::<u64>())
+    }
+
+    fn part2(&self, input: &Vec<InputLine>) -> Answer {
+        Answer::from(process_input_part2(input))
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_14::Day14, "input/day14-input.txt");
+}
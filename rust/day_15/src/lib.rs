@@ -1,4 +1,10 @@
+//! Day 15: Rambunctious Recitation.
+
 use std::collections::HashMap;
+use std::error;
+
+use aoc_common::{ErrorKind, InputError};
+use aoc_solution::{Answer, Solution};
 
 /// Computes the nth (1-based) number in the sequence specified
 /// by part 1 of day 15.
@@ -26,7 +32,8 @@ fn get_nth_number(initial_seq: &[usize], target_turn: usize) -> usize {
 }
 
 
-fn main() {
+#[test]
+fn test_get_nth_number() {
     assert_eq!(get_nth_number(&[0, 3, 6], 4), 0);
     assert_eq!(get_nth_number(&[0, 3, 6], 5), 3);
     assert_eq!(get_nth_number(&[0, 3, 6], 6), 3);
@@ -41,6 +48,28 @@ fn main() {
     assert_eq!(get_nth_number(&[2, 3, 1], 2020), 78);
     assert_eq!(get_nth_number(&[3, 2, 1], 2020), 438);
     assert_eq!(get_nth_number(&[3, 1, 2], 2020), 1836);
-    println!("Part 1: {:?}", get_nth_number(&[7,12,1,0,16,2], 2020));
-    println!("Part 2: {:?}", get_nth_number(&[7,12,1,0,16,2], 30000000));
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Vec<usize>;
+    const DAY: u32 = 15;
+    const TITLE: &'static str = "Rambunctious Recitation";
+
+    /// The input is the starting numbers, separated by commas.
+    fn parse(&self, text: &str) -> Result<Vec<usize>, Box<dyn error::Error>> {
+        match aoc_common::lines(text).next() {
+            Some(line) => Ok(line.number_list(",")?),
+            None => Err(InputError::new(0, ErrorKind::Missing(String::from("starting numbers"))).into()),
+        }
+    }
+
+    fn part1(&self, input: &Vec<usize>) -> Answer {
+        Answer::from(get_nth_number(input, 2020))
+    }
+
+    fn part2(&self, input: &Vec<usize>) -> Answer {
+        Answer::from(get_nth_number(input, 30000000))
+    }
 }
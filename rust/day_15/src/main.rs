@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_15::Day15, "input/day15-input.txt");
+}
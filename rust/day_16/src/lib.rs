@@ -1,11 +1,14 @@
 
+//! Day 16: Ticket Translation.
+
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::{HashMap, HashSet};
-use std::io::{BufRead, BufReader};
-use std::fs::File;
+use std::error;
 use std::ops::Range;
-use std::path::Path;
+
+use aoc_common::{InputError, Line};
+use aoc_solution::{Answer, Solution};
 
 /// A Ticket has an ordered list of numbers
 type Ticket = Vec<u64>;
@@ -69,14 +72,18 @@ fn parse_range_set(s: &str) -> Option<RangeSet> {
     Some(result)
 }
 
-fn parse_field_line(s: &str) -> Option<(String, RangeSet)> {
-    let colon_pos = s.find(":")?;
-    Some((String::from(&s[..colon_pos]), parse_range_set(&s[colon_pos+2 ..])?))
+/// Turns a line like "class: 1-3 or 5-7" into the name of the field
+/// and its ranges.
+fn parse_field_line(line: &Line) -> Result<(String, RangeSet), InputError> {
+    let bad_line = || line.invalid(format!("expected \"field: a-b or c-d\", found {:?}", line.text));
+    let colon_pos = line.text.find(": ").ok_or_else(bad_line)?;
+    let range_set = parse_range_set(&line.text[colon_pos+2 ..]).ok_or_else(bad_line)?;
+    Ok((String::from(&line.text[..colon_pos]), range_set))
 }
 
 /// Structure holding everything in an input file
 #[derive(Debug)]
-struct InputFile {
+pub struct InputFile {
     // mapping from field name on ticket to range
     field_to_range_set: HashMap<String, RangeSet>,
 
@@ -87,56 +94,60 @@ struct InputFile {
     other_tickets: Vec<Ticket>,
 }
 
-/// Turns a comma-separated list of numbers into a Ticket
-fn parse_number_list(s: &str) -> Option<Ticket> {
-    let mut result = Vec::new();
-    for number_str in s.split(",") {
-        result.push(parse_int(number_str)?);
-    }
-    Some(result)
-}
-
-/// Parses an entire input file
-fn parse_input_file(path_str: &str) -> Option<InputFile> {
-
-    // Open the file for reading and make a line iterator
-    let path = Path::new(path_str);
-    let file = File::open(path).unwrap();
-    let reader = BufReader::new(&file);
-    let mut lines = reader.lines().map(|l| l.unwrap());
+/// Parses an entire input file, which has three sections: the fields,
+/// my ticket, and the nearby tickets.
+fn parse_input_file(text: &str) -> Result<InputFile, InputError> {
+    let sections = aoc_common::sections_exactly(text, 3)?;
 
     // read the ranges on each field
     let mut field_to_range_set = HashMap::new();
-    loop {
-        let line: String = lines.next()?;
-        if line.is_empty() {
-            break
-        }
-        let (field, range_set) = parse_field_line(line.as_str())?;
+    for line in sections[0].lines() {
+        let (field, range_set) = parse_field_line(&line)?;
         field_to_range_set.insert(field, range_set);
     }
 
     // my ticket
-    assert_eq!("your ticket:", lines.next()?);
-    let my_ticket = parse_number_list(&lines.next()?)?;
-    assert_eq!("", lines.next()?);
+    expect_header(&sections[1].first(), "your ticket:")?;
+    let field_count = field_to_range_set.len();
+    let my_ticket = match sections[1].rest().next() {
+        Some(line) => parse_ticket(&line, field_count)?,
+        None => return Err(sections[1].first().invalid("missing my ticket")),
+    };
 
     // other tickets
-    assert_eq!("nearby tickets:", lines.next()?);
-    let other_tickets = 
-        lines  
-            .map(|line| parse_number_list(&line).unwrap())
-            .collect();
+    expect_header(&sections[2].first(), "nearby tickets:")?;
+    let other_tickets =
+        sections[2].rest()
+            .map(|line| parse_ticket(&line, field_count))
+            .collect::<Result<Vec<Ticket>, InputError>>()?;
 
-    Some(
+    Ok(
         InputFile{
-            field_to_range_set: field_to_range_set,
-            my_ticket: my_ticket,
-            other_tickets: other_tickets,
+            field_to_range_set,
+            my_ticket,
+            other_tickets,
         }
     )
 }
 
+/// Parses a ticket, which has to have one number for each field.
+fn parse_ticket(line: &Line, field_count: usize) -> Result<Ticket, InputError> {
+    let ticket: Ticket = line.number_list(",")?;
+    if ticket.len() != field_count {
+        let message = format!("expected {} numbers, one for each field, found {}", field_count, ticket.len());
+        return Err(line.invalid(message));
+    }
+    Ok(ticket)
+}
+
+fn expect_header(line: &Line, header: &str) -> Result<(), InputError> {
+    if line.text == header {
+        Ok(())
+    } else {
+        Err(line.invalid(format!("expected {:?}, found {:?}", header, line.text)))
+    }
+}
+
 fn in_any_range(n: &u64, input_file: &InputFile) -> bool {
     for range_set in input_file.field_to_range_set.values() {
         if range_set.contains(*n) {
@@ -176,8 +187,7 @@ fn ticket_has_scan_error(ticket: &Ticket, input_file: &InputFile) -> bool {
 fn tickets_without_scan_errors(input_file: &InputFile) -> Vec<Ticket> {
     input_file.other_tickets
         .iter()
-        .filter(|t| ! ticket_has_scan_error(t, input_file))
-        .map(|t| t.clone())
+        .filter(|t| ! ticket_has_scan_error(t, input_file)).cloned()
         .collect()
 }
 
@@ -190,9 +200,8 @@ fn range_set_matches_column(range_set: &RangeSet, col_index: usize, tickets: &Ve
     true
 }
 
-fn columns_that_match_range_set(tickets: &Vec<Ticket>, range_set: &RangeSet) -> HashSet<usize> {
+fn columns_that_match_range_set(tickets: &Vec<Ticket>, column_count: usize, range_set: &RangeSet) -> HashSet<usize> {
     let mut result = HashSet::new();
-    let column_count = tickets[0].len();
     for col in 0..column_count {
         if range_set_matches_column(range_set, col, tickets) {
             result.insert(col);
@@ -205,16 +214,17 @@ fn compute_names_and_possible_columns(input_file: &InputFile) -> Vec<(String, Ha
     let tickets_to_check = tickets_without_scan_errors(input_file);
     let mut result = Vec::new();
     for (name, range_set) in input_file.field_to_range_set.iter() {
-        result.push((String::from(name), columns_that_match_range_set(&tickets_to_check, range_set)));
+        let column_count = input_file.field_to_range_set.len();
+        result.push((String::from(name), columns_that_match_range_set(&tickets_to_check, column_count, range_set)));
     }
-    result.sort_by(|a, b| a.1.len().cmp(&b.1.len()));
+    result.sort_by_key(|a| a.1.len());
     result
 }
 
 fn column_order_helper(remaining_fields: &[(String, HashSet<usize>)], columns_used: &HashSet<usize>) -> Option<Vec<String>> {
     let column_count = remaining_fields.len() + columns_used.len();
     if remaining_fields.is_empty() {
-        return Some(std::iter::repeat(String::new()).take(column_count).collect())
+        return Some(std::iter::repeat_n(String::new(), column_count).collect())
     }
     let ((field_name, possible_columns), rest) = remaining_fields.split_first().unwrap();
     for candidate in possible_columns {
@@ -222,41 +232,94 @@ fn column_order_helper(remaining_fields: &[(String, HashSet<usize>)], columns_us
             let mut more_columns_used = columns_used.clone();
             more_columns_used.insert(*candidate);
             let option_answer: Option<Vec<String>> = column_order_helper(rest, &more_columns_used);
-            if option_answer.is_some() {
-                let mut answer = option_answer.unwrap();
+            if let Some(mut answer) = option_answer {
                 answer[*candidate] = String::from(field_name);
                 return Some(answer)
             }       
         }
     }
-    println!("no solution found for {:?} {:?}", remaining_fields, columns_used);
     None
 }
-fn column_order(input_file: &InputFile) -> Vec<String> {
+/// The name of the field in each column, or None if there's no way
+/// to give every field its own column.
+fn column_order(input_file: &InputFile) -> Option<Vec<String>> {
     let names_and_possible_columns = compute_names_and_possible_columns(input_file);
-    column_order_helper(&names_and_possible_columns, &HashSet::new()).unwrap()
+    column_order_helper(&names_and_possible_columns, &HashSet::new())
 }
 
-fn main() {
+/// Multiplies together the fields on my ticket whose names start
+/// with "departure", or returns None if the columns can't be matched
+/// up with the fields.
+fn departure_product(input_file: &InputFile) -> Option<u64> {
+    let product = column_order(input_file)?.iter()
+        .enumerate()
+        .filter(|(_, name)| name.starts_with("departure"))
+        .map(|(index, _)| input_file.my_ticket[index])
+        .product();
+    Some(product)
+}
+
+#[test]
+fn test_parse_range() {
     assert_eq!(parse_range("2-10").unwrap(), 2..11);
     assert_eq!(
         parse_range_set("1-4 or 7-8").unwrap(), 
         *RangeSet::new().insert(&(1..5)).insert(&(7..9))
     );
-    println!("Hello, world!");
+}
 
-    let sample_input = parse_input_file("sample.txt").unwrap();
-    println!("{:?}", sample_input);
-    println!("Sample part 1: {:?}", ticket_scanning_error_rate(&sample_input));
+#[test]
+fn test_sample() {
+    let sample_input = parse_input_file(&aoc_common::read_file("sample.txt").unwrap()).unwrap();
+    assert_eq!(ticket_scanning_error_rate(&sample_input), 71);
+}
 
-    let real_input = parse_input_file("input.txt").unwrap();
-    println!("Part 1: {}", ticket_scanning_error_rate(&real_input));
-    let column_order = column_order(&real_input);
-    println!("Column order: {:?}", column_order);
-    let product: u64 = column_order.iter()
-        .enumerate()
-        .filter(|(_, name)| name.starts_with("departure"))
-        .map(|(index, _)| real_input.my_ticket[index])
-        .product();
-    println!("Part 2: {:?}", product);
+#[test]
+fn test_parse_errors() {
+    let error = |text: &str| parse_input_file(text).unwrap_err().to_string();
+    assert_eq!(error("a: 1-2\n\nyour ticket:\n1\n"), "expected 3 sections separated by blank lines, found 2");
+    assert_eq!(error("a: 1-2\n\nyour ticket:\n1\n\nnearby tickets:\n3,x\n"), "line 7: expected a number, found \"x\"");
+    assert_eq!(
+        error("a: 1 to 2\n\nyour ticket:\n1\n\nnearby tickets:\n"),
+        "line 1: expected \"field: a-b or c-d\", found \"a: 1 to 2\""
+    );
+    assert_eq!(
+        error("a: 1-2\nb: 3-4\n\nyour ticket:\n1\n\nnearby tickets:\n"),
+        "line 5: expected 2 numbers, one for each field, found 1"
+    );
+    assert_eq!(
+        error("a: 1-2\nb: 3-4\n\nyour ticket:\n1,3\n\nnearby tickets:\n1,3,4\n"),
+        "line 8: expected 2 numbers, one for each field, found 3"
+    );
+}
+
+#[test]
+fn test_no_column_order() {
+    // Both fields only fit the first column.
+    let text = "a: 1-2 or 8-8\nb: 1-2 or 9-9\n\nyour ticket:\n1,8\n\nnearby tickets:\n1,8\n1,9\n";
+    let input = parse_input_file(text).unwrap();
+    assert_eq!(departure_product(&input), None);
+}
+
+pub struct Day16;
+
+impl Solution for Day16 {
+    type Input = InputFile;
+    const DAY: u32 = 16;
+    const TITLE: &'static str = "Ticket Translation";
+
+    fn parse(&self, text: &str) -> Result<InputFile, Box<dyn error::Error>> {
+        Ok(parse_input_file(text)?)
+    }
+
+    fn part1(&self, input: &InputFile) -> Answer {
+        Answer::from(ticket_scanning_error_rate(input))
+    }
+
+    fn part2(&self, input: &InputFile) -> Answer {
+        match departure_product(input) {
+            Some(product) => Answer::from(product),
+            None => Answer::None,
+        }
+    }
 }
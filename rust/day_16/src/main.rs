@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_16::Day16, "input/day16-input.txt");
+}
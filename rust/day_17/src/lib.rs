@@ -1,11 +1,17 @@
+//! Day 17: Conway Cubes.
+
 use std::convert::TryInto;
 use std::cmp;
+use std::error;
 use std::iter::FromIterator;
 use std::ops::Index;
 use std::ops::IndexMut;
 use std::ops::Range;
 use streaming_iterator::StreamingIterator;
 
+use aoc_common::Grid;
+use aoc_solution::{Answer, Solution};
+
 /// The state of one cube in the pocket dimension.
 #[derive(Clone, Copy, Debug, PartialEq)]
 enum CubeState {
@@ -59,7 +65,7 @@ impl Location {
     /// other coordinates 0.
     fn new_x_y(x: i32, y: i32, dimensions: usize) -> Location {
         Location {
-            dimensions : dimensions,
+            dimensions,
             data : {
                 let mut data = [0i32; MAX_DIMENSIONS];
                 data[0] = x;
@@ -69,7 +75,7 @@ impl Location {
         }
     }
 
-    fn iter(&self) -> LocIter {
+    fn iter(&self) -> LocIter<'_> {
         LocIter { loc : self, next_index: 0 }
     }
 }
@@ -79,7 +85,7 @@ impl FromIterator<i32> for Location {
         let mut dimensions = 0;
         let mut data = [0i32; MAX_DIMENSIONS];
         for c in iter {
-            data[dimensions as usize] = c;
+            data[dimensions] = c;
             dimensions += 1;
         }
         Location { dimensions, data }
@@ -111,7 +117,7 @@ impl<'a> Iterator for LocIter<'a> {
     type Item = i32;
 
     fn next(&mut self) -> Option<i32> {
-        if self.next_index < self.loc.dimensions as usize {
+        if self.next_index < self.loc.dimensions {
             let result = self.loc[self.next_index];
             self.next_index += 1;
             Some(result)
@@ -136,7 +142,7 @@ impl Volume {
 
     fn extend_by_one(&self) -> Volume {
         Volume {
-            ranges: self.ranges.iter().map(|r| extend_range(r)).collect()
+            ranges: self.ranges.iter().map(extend_range).collect()
         }
     }
 
@@ -151,7 +157,7 @@ impl Volume {
             ranges: self.ranges.clone(),  // TODO: ref
             current: {
                 let ranges = &self.ranges;
-                let mut result: Location = ranges.into_iter().map(|r| r.start).collect();
+                let mut result: Location = ranges.iter().map(|r| r.start).collect();
                 // When next is called, it will increment first, so we decrement by one
                 // so the first value returned will be the right one.
                 result[0] -= 1;
@@ -219,7 +225,7 @@ impl State {
 
         let cube_count = 
             capacity.ranges.iter()
-                .map(|r| range_count(r))
+                .map(range_count)
                 .product();
 
         State {
@@ -277,7 +283,7 @@ impl State {
             None => {
                 self.size = Some(
                     Volume {
-                        ranges: (0..self.dimensions()).into_iter().map(|i| (loc[i])..(loc[i]+1)).collect()
+                        ranges: (0..self.dimensions()).map(|i| (loc[i])..(loc[i]+1)).collect()
                     }
                 );
             },
@@ -321,25 +327,24 @@ fn run_cycle(prev: &State) -> State {
 
     let mut si = new_capacity.streaming_iter();
     while let Some(loc) = si.next() {
-        let old_state = prev.get(&loc);
-        let active_count = prev.active_neighbors(&loc);
+        let old_state = prev.get(loc);
+        let active_count = prev.active_neighbors(loc);
         let is_active =
             match old_state {
-                CubeState::Active => (2 <= active_count && active_count <= 3),
-                CubeState::Inactive => (active_count == 3),
+                CubeState::Active => (2..=3).contains(&active_count),
+                CubeState::Inactive => active_count == 3,
             };
         if is_active {
-            result.set_active(&loc);
+            result.set_active(loc);
         }
     }
 
     result
 }
 
-fn parse_initial_state(text: &str, dimensions: usize) -> State {
-    let lines: Vec<&str> = text.split("\n").filter(|l| ! l.is_empty()).collect();
-    let col_count = lines[0].len() as i32;
-    let row_count = lines.len() as i32;
+fn parse_initial_state(grid: &Grid, dimensions: usize) -> State {
+    let col_count = grid.width as i32;
+    let row_count = grid.height as i32;
     let ranges = {
         let mut ranges = vec![0..col_count, 0..row_count];
         for _ in 2..dimensions {
@@ -348,111 +353,90 @@ fn parse_initial_state(text: &str, dimensions: usize) -> State {
         ranges
     };
     let capacity = Volume { ranges };
-    println!("capacity: {:?}", capacity);
     let mut result = State::new(&capacity);
-    for (y, line) in (&lines).iter().enumerate() {
-        for (x, c) in line.chars().enumerate() {
-            if c == '#' {
-                let loc = result.x_y_loc(x as i32, y as i32);
-                result.set_active(&loc);
-            }
-        }
+    for (x, y) in grid.positions(b'#') {
+        let loc = result.x_y_loc(x as i32, y as i32);
+        result.set_active(&loc);
     }
     result
 }
 
-/// Prints out a State in the format used on the web site
-fn print_state(state: &State) {
-    let size = state.size.as_ref().unwrap();
-    println!("size = {:?}", size);
-    let mut prev_z = size.ranges[2].start - 1;  // a Z value than is never used, and won't match the first one
-    let mut si = size.streaming_iter();
-    while let Some(loc) = si.next() {
-        if prev_z != loc[2] {
-            println!("AAA {:?}", loc);
-        }
-        let s = state.get(&loc);
-        let c = if s == CubeState::Active { '#' } else { '.' };
-        print!("{}", c); 
-
-        if loc[0] == size.ranges[0].end - 1 {
-            println!();
-            if loc[1] == size.ranges[1].end - 1 {
-                println!();
-            }
-        }
-        prev_z = loc[2];
-    }
+#[cfg(test)]
+fn test_state() -> Grid {
+    aoc_common::grid(aoc_common::lines(".#.\n..#\n###\n"), b"#.").unwrap()
 }
 
-const TEST_STATE: &str = "
-.#.
-..#
-###
-";
-
-const MY_INPUT: &str = "
-...###.#
-#.#.##..
-.##.##..
-..##...#
-.###.##.
-.#..##..
-.....###
-.####..#
-";
-
-fn run_one(initial: &str, dimensions: usize) {
+/// Runs the six cycles of the boot process, and returns the number
+/// of active cubes at the end.
+fn count_active_after_boot(initial: &Grid, dimensions: usize) -> usize {
     let mut state = parse_initial_state(initial, dimensions);
-    println!("Initial state:");
-    print_state(&state);
-
-    for cycle in 1..7 {
+    for _ in 0..6 {
         state = run_cycle(&state);
-        println!("After cycle {:?}:", cycle);
-        print_state(&state);
     }
-    println!("Active cell total: {:?}", state.count_active())
+    state.count_active()
 }
 
-fn main() {
-    
-    {
-        let volume = Volume { ranges: vec![0..2, 2..4, 4..6] };
-        let mut iter_result: Vec<Vec<i32>> = Vec::new();
-        let mut si = volume.streaming_iter();
-        while let Some(loc) = si.next() {
-            iter_result.push(loc.iter().collect());
-        }
-        assert_eq!(
-            vec![
-                vec![0, 2, 4],
-                vec![1, 2, 4],
-                vec![0, 3, 4],
-                vec![1, 3, 4],
-                vec![0, 2, 5],
-                vec![1, 2, 5],
-                vec![0, 3, 5],
-                vec![1, 3, 5],
-            ],
-            iter_result
-        )
+#[test]
+fn test_volume_iter() {
+    let volume = Volume { ranges: vec![0..2, 2..4, 4..6] };
+    let mut iter_result: Vec<Vec<i32>> = Vec::new();
+    let mut si = volume.streaming_iter();
+    while let Some(loc) = si.next() {
+        iter_result.push(loc.iter().collect());
     }
+    assert_eq!(
+        vec![
+            vec![0, 2, 4],
+            vec![1, 2, 4],
+            vec![0, 3, 4],
+            vec![1, 3, 4],
+            vec![0, 2, 5],
+            vec![1, 2, 5],
+            vec![0, 3, 5],
+            vec![1, 3, 5],
+        ],
+        iter_result
+    )
+}
+
+#[test]
+fn test_parse_initial_state() {
+    let initial = parse_initial_state(&test_state(), 3);
+    let mut expected = State::new(&Volume { ranges: vec![0..3, 0..3, 0..1] });
+    expected.set_active(&expected.x_y_loc(1, 0));
+    expected.set_active(&expected.x_y_loc(2, 1));
+    expected.set_active(&expected.x_y_loc(0, 2));
+    expected.set_active(&expected.x_y_loc(1, 2));
+    expected.set_active(&expected.x_y_loc(2, 2));
+    assert_eq!(initial, expected);
+}
+
+#[test]
+fn test_sample() {
+    assert_eq!(count_active_after_boot(&test_state(), 3), 112);
+    assert_eq!(count_active_after_boot(&test_state(), 4), 848);
+}
 
-    {
-        let initial = parse_initial_state(TEST_STATE, 3);
-        let mut expected = State::new(&Volume { ranges: vec![0..3, 0..3, 0..1] });
-        expected.set_active(&expected.x_y_loc(1, 0));
-        expected.set_active(&expected.x_y_loc(2, 1));
-        expected.set_active(&expected.x_y_loc(0, 2));
-        expected.set_active(&expected.x_y_loc(1, 2));
-        expected.set_active(&expected.x_y_loc(2, 2));
-        assert_eq!(initial, expected);
+/// The initial state of the pocket dimension: a rectangle of `#`
+/// (active) and `.` (inactive) cubes.
+pub struct InitialState(Grid);
+
+pub struct Day17;
+
+impl Solution for Day17 {
+    type Input = InitialState;
+    const DAY: u32 = 17;
+    const TITLE: &'static str = "Conway Cubes";
+
+    fn parse(&self, text: &str) -> Result<InitialState, Box<dyn error::Error>> {
+        Ok(InitialState(aoc_common::grid(aoc_common::lines(text), b"#.")?))
     }
 
-    run_one(TEST_STATE, 4);
-    run_one(MY_INPUT, 4);
+    fn part1(&self, input: &InitialState) -> Answer {
+        Answer::from(count_active_after_boot(&input.0, 3))
+    }
 
-    // part 1: 848
-    // part 2: 1980
+    fn part2(&self, input: &InitialState) -> Answer {
+        Answer::from(count_active_after_boot(&input.0, 4))
+    }
 }
@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_17::Day17, "input/day17-input.txt");
+}
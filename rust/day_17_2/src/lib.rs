@@ -1,14 +1,18 @@
-use std::collections::HashMap;
+//! Day 17: Conway Cubes, again, keeping just the set of active cubes
+//! and packing each location into one number.
+
 use std::collections::HashSet;
 
+use conway_life::LifeRule;
+
 type Location = i64;
 
 fn x_y(x: i64, y: i64) -> Location {
-    assert!(0 <= x && x < 10 && 0 <= y && y < 10);
+    assert!((0..10).contains(&x) && (0..10).contains(&y));
     y << 8 | x
 }
 
-fn neighbor_offsets(dims: usize) -> Vec<i64> {
+pub fn neighbor_offsets(dims: usize) -> Vec<i64> {
     let mut result: Vec<i64> = Vec::new();
     neighbor_offsets_helper(dims, 0, &mut result);
     result
@@ -20,7 +24,7 @@ fn neighbor_offsets_helper(dims: usize, number_so_far: i64, output: &mut Vec<i64
             output.push(number_so_far);
         }
     } else {
-        let offset: i64 = 1 << (dims - 1) * 8;
+        let offset: i64 = 1 << ((dims - 1) * 8);
         for i in -1..=1 {
             neighbor_offsets_helper(dims - 1, number_so_far + i * offset, output);
         }
@@ -40,6 +44,7 @@ fn parse_initial2(bytes: &[u8]) -> HashSet<i64> {
 }
 
 /// Parses an initial state, returning the set of active locations.
+#[cfg(test)]
 fn parse_initial(bytes: &[u8]) -> HashSet<i64> {
     bytes
         .split(|&b| b == b'\n')
@@ -51,38 +56,34 @@ fn parse_initial(bytes: &[u8]) -> HashSet<i64> {
 }
 
 /// Reads the input and returns the set of locations that have active cubes.
-fn read_input() -> HashSet<i64> {
+pub fn read_input() -> HashSet<i64> {
     parse_initial2(include_bytes!("../input.txt"))
 }
 
-fn run_cycle(active_before: &HashSet<i64>, neighbors: &Vec<i64>) -> HashSet<i64> {
-    // Count the neighbors of each cell
-    let mut counts: HashMap<i64, usize> = HashMap::new();
-    for loc in active_before {
-        for offset in neighbors {
-            let neighbor = loc + offset;
-            let entry = counts.entry(neighbor).or_insert(0);
-            *entry += 1;
-        }
-    }
+/// Runs one cycle with Conway's rule: an active cube stays active with
+/// 2 or 3 active neighbors, and an inactive one becomes active with 3.
+pub fn run_cycle(active_before: &HashSet<i64>, neighbors: &[i64]) -> HashSet<i64> {
+    conway_life::conway_step(active_before, neighbors, &LifeRule::conway())
+}
 
-    // Decide the new state of each cell.  The only cells that could possibly
-    // be active are the ones that have at least one neighbor and apper in 
-    // `counts`.
-    counts.into_iter()
-        .filter(|(loc, count)| (*count == 3) || (*count == 2 && active_before.contains(&loc)))
-        .map(|(loc, _)| loc)
-        .collect()
+#[test]
+fn test_parsers_agree() {
+    let text = b".#.\n..#\n###\n";
+    assert_eq!(parse_initial(text), parse_initial2(text));
+    assert_eq!(parse_initial(text).len(), 5);
 }
 
-fn main() {
-    for dims in 3..=4 {
-        println!("\n#\n# {:?} dimensions\n#\n", dims);
-        let mut state = read_input();
-        println!("0: {:?}", state.len());
-        for i in 0..6 {
-            state = run_cycle(&state, &neighbor_offsets(dims));
-            println!("{:?}: {:?}", i + 1, state.len())
-        }
+#[test]
+fn test_run_cycle() {
+    let mut state = parse_initial2(b".#.\n..#\n###\n");
+    for _ in 0..6 {
+        state = run_cycle(&state, &neighbor_offsets(3));
     }
+    assert_eq!(state.len(), 112);
+}
+
+#[test]
+fn test_neighbor_offsets() {
+    assert_eq!(neighbor_offsets(3).len(), 26);
+    assert_eq!(neighbor_offsets(4).len(), 80);
 }
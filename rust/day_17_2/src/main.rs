@@ -0,0 +1,11 @@
+fn main() {
+    for dims in 3..=4 {
+        println!("\n#\n# {} dimensions\n#\n", dims);
+        let mut state = day_17_2::read_input();
+        println!("0: {}", state.len());
+        for i in 0..6 {
+            state = day_17_2::run_cycle(&state, &day_17_2::neighbor_offsets(dims));
+            println!("{}: {}", i + 1, state.len())
+        }
+    }
+}
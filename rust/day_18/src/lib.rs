@@ -0,0 +1,204 @@
+// Day 18 of Advent of Code 2020.
+//
+// The task is to evaluate infix expressions where the
+// operators all have the same precedence, and are left
+// associative.
+//
+// Assumes that all of the numbers in the input are single
+// digits.
+
+use std::error;
+
+use aoc_common::{ErrorKind, InputError, Line};
+use aoc_solution::{Answer, Solution};
+
+/// A reader with lookahead.  Unlike a streaming iterator,
+/// this returns a copy of the current value, not a ref to it.
+struct Reader<'a> {
+    iter: &'a mut dyn Iterator<Item = char>,
+    curr: Option<char>,
+}
+
+impl<'a> Reader<'a> {
+    fn new(iter: &'a mut dyn Iterator<Item = char>) -> Self {
+        let curr = iter.next();
+        Reader { iter, curr }
+    }
+
+    fn current(&self) -> Option<char> {
+        self.curr
+    }
+
+    fn advance(&mut self) {
+        self.curr = self.iter.next()
+    }
+
+    fn expect_and_skip(&mut self, c: char) {
+        assert!(self.curr.unwrap() == c);
+        self.advance();
+    }
+}
+
+/// Applies an operator to two arguments
+fn apply_op(op: char, a: i64, b: i64) -> i64 {
+    match op {
+        '+' => a + b,
+        '*' => a * b,
+        _ => panic!("unknown op: {:?}", op),
+    }
+}
+
+struct Evaluator {
+    /// Sets of operators, by precedence, with least tightly binding first
+    op_levels: Vec<Vec<char>>,
+}
+
+impl Evaluator {
+    /// Evaluates a "primary", which is either a number or a 
+    /// parenthesized expression
+    fn eval_primary(&self, chars: &mut Reader) -> i64 {
+        let c = chars.current().unwrap();
+        chars.advance();
+        if c == '(' {
+            let result = self.eval_ops(0, chars);
+            chars.expect_and_skip(')');
+            result
+        } else if c.is_ascii_digit() {
+            c.to_digit(10).unwrap() as i64
+        } else {
+            panic!("bad char starting primary: {:?}", c);
+        }
+    }
+
+    /// Evaluates an expression, going until reaching the end of the
+    /// input, or a closing paren.
+    fn eval_ops(&self, level: usize, chars: &mut Reader) -> i64 {
+        if level == self.op_levels.len() {
+            self.eval_primary(chars)
+        } else {
+            let mut result = self.eval_ops(level + 1, chars);
+            loop {
+                match chars.current() {
+                    None => break,
+                    Some(c) => {
+                        if self.op_levels[level].contains(&c) {
+                            chars.advance();
+                            result = apply_op(c, result, self.eval_ops(level + 1, chars));
+                        } else {
+                            break
+                        }
+                    },
+                }
+            }
+            result
+        }
+    }
+
+    /// Evaluates a string containing a complete expression.
+    fn eval_string(&self, expr: &str) -> i64 {
+        let mut non_space_chars = expr.chars().filter(|c| *c != ' ');
+        let mut reader = Reader::new(&mut non_space_chars);
+        self.eval_ops(0, &mut reader)
+    }
+}
+
+fn eval_input(evaluator: &Evaluator, expressions: &[String]) -> i64 {
+    expressions.iter()
+        .map(|line| evaluator.eval_string(line))
+        .sum()
+}
+
+#[test]
+fn test_part1_eval() {
+    let part1_eval = Evaluator { op_levels: vec![ vec!['+', '*'] ] };
+    assert_eq!(part1_eval.eval_string("5"), 5);
+    assert_eq!(part1_eval.eval_string("2 * 3 + (4 * 5)"), 26);
+    assert_eq!(part1_eval.eval_string("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"), 13632);
+}
+
+#[test]
+fn test_part2_eval() {
+    let part2_eval = Evaluator { op_levels: vec![ vec!['*'], vec!['+'] ] };
+    assert_eq!(part2_eval.eval_string("5"), 5);
+    assert_eq!(part2_eval.eval_string("2 * 3 + (4 * 5)"), 46);
+    assert_eq!(part2_eval.eval_string("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2"), 23340);
+}
+
+/// Checks that a line is an expression the evaluator can handle:
+/// single-digit numbers and parenthesized expressions, with an
+/// operator between each two of them, and the parentheses balanced.
+fn check_expression(line: &Line) -> Result<(), InputError> {
+    let mut expect_operand = true;
+    let mut depth = 0;
+    for c in line.text.chars().filter(|c| *c != ' ') {
+        if ! "0123456789+*()".contains(c) {
+            return Err(line.error(ErrorKind::UnexpectedChar(c)));
+        }
+        if expect_operand {
+            match c {
+                '(' => depth += 1,
+                '0'..='9' => expect_operand = false,
+                _ => return Err(line.invalid(format!("expected a number or '(', found {:?}", c))),
+            }
+        } else {
+            match c {
+                '+' | '*' => expect_operand = true,
+                ')' if depth == 0 => return Err(line.invalid("')' without a matching '('")),
+                ')' => depth -= 1,
+                _ => return Err(line.invalid(format!("expected an operator or ')', found {:?}", c))),
+            }
+        }
+    }
+    if expect_operand {
+        Err(line.invalid("expected a number or '(', found the end of the line"))
+    } else if depth != 0 {
+        Err(line.invalid("'(' without a matching ')'"))
+    } else {
+        Ok(())
+    }
+}
+
+/// Reads one expression per line, checking that each one is an
+/// expression the evaluator can handle.
+fn parse_expressions(text: &str) -> Result<Vec<String>, InputError> {
+    let mut result = Vec::new();
+    for line in aoc_common::lines(text) {
+        check_expression(&line)?;
+        result.push(String::from(line.text));
+    }
+    Ok(result)
+}
+
+#[test]
+fn test_parse_expressions() {
+    assert_eq!(parse_expressions("1 + 2\n\n3 * 4\n").unwrap(), vec!["1 + 2", "3 * 4"]);
+    assert_eq!(parse_expressions("1 + 2\n3 - 4\n").unwrap_err().to_string(), "line 2: unexpected character '-'");
+    let error = |text: &str| parse_expressions(text).unwrap_err().to_string();
+    assert_eq!(error("1 +"), "line 1: expected a number or '(', found the end of the line");
+    assert_eq!(error("(1 + 2"), "line 1: '(' without a matching ')'");
+    assert_eq!(error("1 + 2)"), "line 1: ')' without a matching '('");
+    assert_eq!(error("12 + 3"), "line 1: expected an operator or ')', found '2'");
+    assert_eq!(error("1 + * 3"), "line 1: expected a number or '(', found '*'");
+    assert_eq!(error("()"), "line 1: expected a number or '(', found ')'");
+}
+
+pub struct Day18;
+
+impl Solution for Day18 {
+    /// One expression per line
+    type Input = Vec<String>;
+    const DAY: u32 = 18;
+    const TITLE: &'static str = "Operation Order";
+
+    fn parse(&self, text: &str) -> Result<Vec<String>, Box<dyn error::Error>> {
+        Ok(parse_expressions(text)?)
+    }
+
+    fn part1(&self, input: &Vec<String>) -> Answer {
+        Answer::from(eval_input(&Evaluator { op_levels: vec![ vec!['+', '*'] ] }, input))
+    }
+
+    fn part2(&self, input: &Vec<String>) -> Answer {
+        Answer::from(eval_input(&Evaluator { op_levels: vec![ vec!['*'], vec!['+'] ] }, input))
+    }
+}
@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_18::Day18, "input/day18-input.txt");
+}
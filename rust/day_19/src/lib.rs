@@ -0,0 +1,244 @@
+//! Day 19: Monster Messages.
+
+// use lazy_static::lazy_static;
+use regex::Regex;
+use std::collections::HashMap;
+use std::error;
+
+use aoc_common::{ErrorKind, InputError, Line};
+use aoc_solution::{Answer, Solution};
+
+#[derive(Clone, Debug, PartialEq)]
+enum Pattern {
+    Text(String),
+    Choices(Vec<Pattern>),
+    RuleNumbers(Vec<usize>),
+}
+
+fn parse_pattern(text: &str, line: &Line) -> Result<Pattern, InputError> {
+    // lazy_static! {
+    //     static ref TEXT_PATTERN: Regex = Regex::new("\"(.*)\"").unwrap();
+    // }
+    let text_pattern = Regex::new("\"(.*)\"").unwrap();
+    if text_pattern.is_match(text) {
+        let captures = text_pattern.captures(text).unwrap();
+        let text = captures.get(1).unwrap().as_str();
+        Ok(Pattern::Text(String::from(text)))
+    } else if text.contains("|") {
+        Ok(Pattern::Choices(
+            text.split("|").map(|s| parse_pattern(s.trim(), line)).collect::<Result<_, _>>()?
+        ))
+    } else {
+        let numbers: Vec<usize> = text.split(" ")
+            .filter(|s| ! s.is_empty())
+            .map(|s| aoc_common::parse_number(s.trim(), line.number))
+            .collect::<Result<_, _>>()?;
+        Ok(Pattern::RuleNumbers(numbers))
+    }
+}
+
+fn parse_rule(line: &Line) -> Result<(usize, Pattern), InputError> {
+    let colon_pos = line.text.find(':').ok_or_else(|| line.invalid(format!("expected a rule, found {:?}", line.text)))?;
+    let num = aoc_common::parse_number(line.text[..colon_pos].trim(), line.number)?;
+    Ok((num, parse_pattern(&line.text[colon_pos+1 ..], line)?))
+}
+
+#[derive(Clone, Debug)]
+pub struct Input {
+    rules: HashMap<usize, Pattern>,
+    messages: Vec<String>,
+}
+
+/// Returns a rule number used in the pattern that isn't one of the
+/// rules, if there is one.
+fn undefined_rule(pattern: &Pattern, rules: &HashMap<usize, Pattern>) -> Option<usize> {
+    match pattern {
+        Pattern::Text(_) => None,
+        Pattern::Choices(choices) => choices.iter().find_map(|choice| undefined_rule(choice, rules)),
+        Pattern::RuleNumbers(numbers) => numbers.iter().cloned().find(|n| ! rules.contains_key(n)),
+    }
+}
+
+fn parse_input(text: &str) -> Result<Input, InputError> {
+    // The input is in two sections, separated by a blank line
+    let sections = aoc_common::sections_exactly(text, 2)?;
+
+    // The first section is a set of rules, one per line
+    let mut rules: HashMap<usize, Pattern> = HashMap::new();
+    let mut rule_lines: Vec<(Line, usize)> = Vec::new();
+    for line in sections[0].lines() {
+        let (num, pattern) = parse_rule(&line)?;
+        if rules.insert(num, pattern).is_some() {
+            return Err(line.invalid(format!("rule {} is there twice", num)));
+        }
+        rule_lines.push((line, num));
+    }
+
+    // Every rule a rule refers to has to be there
+    for (line, num) in rule_lines {
+        if let Some(missing) = undefined_rule(&rules[&num], &rules) {
+            return Err(line.invalid(format!("there is no rule {}", missing)));
+        }
+    }
+
+    // The second section is a list of messages to check, one per line
+    let messages: Vec<String> =
+        sections[1].lines()
+            .map(|line| String::from(line.text))
+            .collect();
+
+    if ! rules.contains_key(&0) {
+        return Err(sections[0].error(ErrorKind::Missing(String::from("rule 0"))));
+    }
+
+    Ok(Input { rules, messages })
+}
+
+type StrPred<'a> = &'a dyn Fn(&str) -> bool;
+
+fn match_rule_numbers(input: &Input, numbers: &[usize], remaining: &StrPred, text: &str) -> bool
+{
+    if numbers.is_empty() {
+        remaining(text)
+    } else {
+        let match_rest: StrPred = 
+            &|subtext| 
+                match_rule_numbers(input, &numbers[1..], remaining, subtext); 
+        match_pattern(
+            input, 
+            input.rules.get(&numbers[0]).unwrap(), 
+            &match_rest,
+            text
+        )
+    }
+}
+
+fn match_pattern(input: &Input, pattern: &Pattern, remaining: &StrPred, text: &str) -> bool
+{
+    match pattern {
+        Pattern::Text(s) => {
+            if text.starts_with(s) {
+                remaining(&text[s.len()..])
+            } else {
+                false
+            }
+        },
+        Pattern::Choices(choices) => {
+            choices.iter().any(
+                |choice| match_pattern(input, choice, remaining, text)
+            )
+        },
+        Pattern::RuleNumbers(numbers) => {
+            match_rule_numbers(input, numbers, remaining, text)
+        }
+    }
+}
+
+fn run_part1(input: &Input) -> usize {
+    let rule0 = &input.rules[&0];
+    let match_empty: StrPred = &mut |t| t.is_empty();
+    input.messages.iter()
+        .filter(|m| match_pattern(input, rule0, &match_empty, m))
+        .count()
+}
+
+/// Counts the messages that match rule 0 when rules 8 and 11 loop.
+/// The new rules 8 and 11 use rules 42 and 31, so without them there
+/// is no answer.
+fn run_part2(original: &Input) -> Option<usize> {
+    if ! (original.rules.contains_key(&42) && original.rules.contains_key(&31)) {
+        return None;
+    }
+    let mut input = original.clone();
+    input.rules.insert(
+        8,
+        Pattern::Choices(
+            vec![
+                Pattern::RuleNumbers(vec![42]),
+                Pattern::RuleNumbers(vec![42, 8]),
+            ]
+        )
+    );
+    input.rules.insert(
+        11,
+        Pattern::Choices(
+            vec![
+                Pattern::RuleNumbers(vec![42, 31]),
+                Pattern::RuleNumbers(vec![42, 11, 31]),
+            ]
+        )
+    );
+    Some(run_part1(&input))
+}
+
+#[cfg(test)]
+fn parse_input_file(file_name: &str) -> Input {
+    parse_input(&aoc_common::read_file(file_name).unwrap()).unwrap()
+}
+
+#[test]
+fn test_parse_rule() {
+    let rule = |text| parse_rule(&Line { number: 1, text });
+    assert_eq!(rule("121: \"a\"").unwrap(), (121, Pattern::Text(String::from("a"))));
+    assert_eq!(
+        rule("124: 121 125 | 48 121").unwrap(),
+        (
+            124,
+            Pattern::Choices(
+                vec![
+                     Pattern::RuleNumbers(vec![121, 125]),
+                     Pattern::RuleNumbers(vec![48, 121]),
+                ]
+             )
+        )
+    );
+    assert_eq!(rule("124: 121 x").unwrap_err().to_string(), "line 1: expected a number, found \"x\"");
+    assert_eq!(rule("124 121").unwrap_err().to_string(), "line 1: expected a rule, found \"124 121\"");
+    assert_eq!(parse_input("1: \"a\"\n\na\n").unwrap_err().to_string(), "line 1: missing rule 0");
+    assert_eq!(
+        parse_input("0: 1 2\n1: \"a\"\n2: 1 | 3 1\n\na\n").unwrap_err().to_string(),
+        "line 3: there is no rule 3"
+    );
+    assert_eq!(parse_input("0: 1\n1: \"a\"\n1: \"b\"\n\na\n").unwrap_err().to_string(), "line 3: rule 1 is there twice");
+}
+
+#[test]
+fn test_sample() {
+    let sample = parse_input_file("sample.txt");
+    let match_empty: StrPred = &mut |t| t.is_empty();
+    assert!(match_pattern(&sample, &Pattern::Text(String::from("a")), &match_empty, "a"));
+    assert!(match_pattern(&sample, &Pattern::RuleNumbers(vec![4]), &match_empty, "a"));
+    assert!(match_pattern(&sample, &Pattern::RuleNumbers(vec![4, 5]), &match_empty, "ab"));
+    assert_eq!(run_part1(&sample), 2);
+}
+
+#[test]
+fn test_sample2() {
+    let sample2 = parse_input_file("sample2.txt");
+    assert_eq!(run_part1(&sample2), 3);
+    assert_eq!(run_part2(&sample2), Some(12));
+    assert_eq!(run_part2(&parse_input_file("sample.txt")), None);
+}
+
+pub struct Day19;
+
+impl Solution for Day19 {
+    type Input = Input;
+    const DAY: u32 = 19;
+    const TITLE: &'static str = "Monster Messages";
+
+    fn parse(&self, text: &str) -> Result<Input, Box<dyn error::Error>> {
+        Ok(parse_input(text)?)
+    }
+
+    fn part1(&self, input: &Input) -> Answer {
+        Answer::from(run_part1(input))
+    }
+
+    fn part2(&self, input: &Input) -> Answer {
+        match run_part2(input) {
+            Some(count) => Answer::from(count),
+            None => Answer::None,
+        }
+    }
+}
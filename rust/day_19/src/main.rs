@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_19::Day19, "input/day19-input.txt");
+}
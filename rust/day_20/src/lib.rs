@@ -1,7 +1,12 @@
 
+//! Day 20: Jurassic Jigsaw.
+
 use std::collections::HashSet;
+use std::error;
 use std::fmt::Formatter;
-use std::fs::read_to_string;
+
+use aoc_common::{ErrorKind, InputError, Section};
+use aoc_solution::{Answer, Solution};
 
 /// Returns the square root of a usize.
 /// Panics if the number is not a perfect square.
@@ -42,7 +47,7 @@ impl Edge {
 }
 
 #[derive(Clone, Eq, PartialEq)]
-struct Tile {
+pub struct Tile {
     number: usize,    // which tile is this?
     pixels: Grid<u8>, // the array of pixels in this tile
     top: Edge,        // left-to-right
@@ -74,35 +79,63 @@ impl Tile {
     }
 }
 
-fn parse_tile(text: &str) -> Tile {
-    let mut lines_iter = text.split("\n").filter(|s| ! s.is_empty());
+/// Parses one tile: a header like "Tile 1234:" and then a square of
+/// pixels.
+fn parse_tile(section: &Section) -> Result<Tile, InputError> {
+    let header = section.first();
+    let tile_num = match header.text.strip_prefix("Tile ").and_then(|s| s.strip_suffix(':')) {
+        Some(number) => aoc_common::parse_number(number, header.number)?,
+        None => return Err(header.invalid(format!("bad tile header: {:?}", header.text))),
+    };
 
-    let header = lines_iter.next().unwrap();
-    let tile_num = header[5..9].parse::<usize>().unwrap();
-
-    let tile_bytes: Vec<u8> = 
-        lines_iter 
-            .flat_map(|line| line.as_bytes().iter())
-            .map(|&b| b)
-            .collect();
+    if section.rest().next().is_none() {
+        return Err(header.error(ErrorKind::Missing(format!("the pixels of tile {}", tile_num))));
+    }
+    let pixels = aoc_common::grid(section.rest(), b"#.")?;
+    if pixels.width != pixels.height {
+        return Err(header.invalid(format!("tile {} is not square", tile_num)));
+    }
+    let tile_bytes: Vec<u8> = pixels.rows().flatten().copied().collect();
 
-    Tile::new(tile_num, Grid::square_from_vec(tile_bytes))
+    Ok(Tile::new(tile_num, Grid::square_from_vec(tile_bytes)))
 }
 
 #[test]
 fn test_parse_tile() {
+    let tile = |text| parse_tile(&Section { first_line: 1, text });
     assert_eq!(
-        parse_tile("Tile 1234:\n##..\n...#\n....\n..#.\n"),
+        tile("Tile 1234:\n##..\n...#\n....\n..#.\n").unwrap(),
         Tile::new(1234, Grid::square_from_vec(b"##.....#......#.".to_vec()))
     );
+    assert_eq!(tile("Tile 12:\n##.\n...\n").unwrap_err().to_string(), "line 1: tile 12 is not square");
+    assert_eq!(tile("Tile 12\n#\n").unwrap_err().to_string(), "line 1: bad tile header: \"Tile 12\"");
+    assert_eq!(tile("Tile 12:\n").unwrap_err().to_string(), "line 1: missing the pixels of tile 12");
+}
+
+/// Parses all of the tiles in an input file, checking that there are
+/// enough of them to make a square.
+fn parse_tiles(text: &str) -> Result<Vec<Tile>, InputError> {
+    let result = aoc_common::sections(text).iter().map(parse_tile).collect::<Result<Vec<Tile>, InputError>>()?;
+    if result.len() != usize_sqrt_floor(result.len()).pow(2) {
+        return Err(InputError::new(0, ErrorKind::Invalid(
+            format!("{} tiles can't be made into a square", result.len())
+        )));
+    }
+    Ok(result)
+}
+
+/// Returns the largest number whose square is not more than `n`.
+fn usize_sqrt_floor(n: usize) -> usize {
+    let mut result = (n as f64).sqrt() as usize;
+    while n < result * result {
+        result -= 1;
+    }
+    result
 }
 
+#[cfg(test)]
 fn read_input(file_name: &str) -> Vec<Tile> {
-    read_to_string(file_name)
-        .unwrap()
-        .split("\n\n")
-        .map(|text| parse_tile(text)) 
-        .collect()
+    parse_tiles(&aoc_common::read_file(file_name).unwrap()).unwrap()
 }
 
 // struct TileLibrary {
@@ -175,7 +208,7 @@ impl<T: Clone + std::fmt::Debug> Grid<T> {
     /// Creates a new grid, with values supplied from a slice of values.
     fn from_vec(width: usize, height: usize, items: Vec<T>) -> Grid<T> {
         assert_eq!(width * height, items.len());
-        Grid { width: width, height: height, items }
+        Grid { width, height, items }
     }
 
     /// Creates a new grid, with values supplied from a slice of values.
@@ -246,21 +279,21 @@ impl<T: Clone + std::fmt::Debug> Grid<T> {
 
     // The right edge
     fn right_edge(&self) -> Vec<T> {
-        (0..self.height).into_iter()
+        (0..self.height)
             .map(|i| self.get(GridPos::new(self.width - 1, i)).clone())
             .collect()
     }
 
     // The bottom edge
     fn bottom_edge(&self) -> Vec<T> {
-        (0..self.width).into_iter()
+        (0..self.width)
             .map(|i| self.get(GridPos::new(i, self.height - 1)).clone())
             .collect()
     }
 
     // The left edge
     fn left_edge(&self) -> Vec<T> {
-        (0..self.height).into_iter()
+        (0..self.height)
             .map(|i| self.get(GridPos::new(0, i)).clone())
             .collect()
     }
@@ -307,7 +340,7 @@ impl<T: std::fmt::Debug> std::fmt::Debug for Grid<T> {
             if i % self.width == 0 {
                 f.write_str("\n")?;
             }
-            write!(f, "{:?}", item);
+            write!(f, "{:?}", item)?;
         }
         f.write_str("\n")
     }
@@ -448,8 +481,9 @@ fn solve_part1<'a, 'b>(
     }
 }
 
-fn part1(file_name: &str) -> Grid<Tile> {
-    let tiles_from_input = read_input(file_name);
+/// Puts the tiles together into a square grid, with all of the
+/// edges matching.
+fn assemble(tiles_from_input: &[Tile]) -> Grid<Tile> {
     let size = usize_sqrt(tiles_from_input.len());
 
     let choices: Vec<Tile> =
@@ -481,10 +515,12 @@ fn part1(file_name: &str) -> Grid<Tile> {
         }
     }
 
-    let answer = answers.pop().unwrap();
-    let part1_product: usize = answer.corners().iter().map(|t| t.number).product();
-    println!("Part 1: {:?}", part1_product);
-    answer
+    answers.pop().unwrap()
+}
+
+/// Multiplies together the numbers of the corner tiles.
+fn part1(tile_grid: &Grid<Tile>) -> usize {
+    tile_grid.corners().iter().map(|t| t.number).product()
 }
 
 fn combine_tiles(tile_grid: &Grid<Tile>) -> Grid<u8> {
@@ -510,17 +546,6 @@ fn combine_tiles(tile_grid: &Grid<Tile>) -> Grid<u8> {
     Grid::from_vec(image_size, image_size, image_pixels)
 }
 
-fn print_image(pixels: &Grid<u8>) {
-    for (i, item) in pixels.items.iter().enumerate() {
-        if i % pixels.width == 0 {
-            print!("\n");
-        }
-        let c: char = *item as char;
-        print!("{}", c);
-    }
-    print!("\n")
-}
-
 fn make_sea_monster() -> Grid<u8> {
     let pixels = "                  # #    ##    ##    ### #  #  #  #  #  #   ".as_bytes();
     Grid::from_vec(20, 3, pixels.to_vec())
@@ -529,11 +554,10 @@ fn make_sea_monster() -> Grid<u8> {
 fn image_matches(full_image: &Grid<u8>, sub_image: &Grid<u8>, dx: usize, dy: usize) -> bool {
     for x in 0..sub_image.width {
         for y in 0..sub_image.height {
-            if *sub_image.get(GridPos::new(x, y)) == b'#' {
-                if *full_image.get(GridPos::new(x + dx, y + dy)) != b'#' {
+            if *sub_image.get(GridPos::new(x, y)) == b'#'
+                && *full_image.get(GridPos::new(x + dx, y + dy)) != b'#' {
                     return false;
                 }
-            }
         }
     }
     true
@@ -549,7 +573,9 @@ fn set_sub_image(full_image: &mut Grid<u8>, sub_image: &Grid<u8>, dx: usize, dy:
     }
 }
 
-fn part2(tile_grid: &Grid<Tile>) {
+/// Finds the sea monsters, and returns the number of `#` pixels that
+/// aren't part of one.
+fn part2(tile_grid: &Grid<Tile>) -> usize {
     let full_image = combine_tiles(tile_grid);
     let sea_monster = make_sea_monster();
 
@@ -564,19 +590,35 @@ fn part2(tile_grid: &Grid<Tile>) {
             }
         }
         if match_count != 0 {
-            print_image(&grid);
-            let roughness = grid.items.iter().filter(|&&b| b == b'#').count();
-            println!("Part 2 (roughness): {:?}", roughness);
+            return grid.items.iter().filter(|&&b| b == b'#').count();
         }
     }
+    panic!("no sea monsters found")
 }
 
-fn main() {
-    println!("SAMPLE:");
-    let sample1_grid = part1("sample1.txt");  // 20899048083289
-    part2(&sample1_grid);
+#[test]
+fn test_sample() {
+    let sample1_grid = assemble(&read_input("sample1.txt"));
+    assert_eq!(part1(&sample1_grid), 20899048083289);
+    assert_eq!(part2(&sample1_grid), 273);
+}
 
-    println!("\nREAL:");
-    let real_grid = part1("input.txt");  // 22878471088273
-    part2(&real_grid);
+pub struct Day20;
+
+impl Solution for Day20 {
+    type Input = Vec<Tile>;
+    const DAY: u32 = 20;
+    const TITLE: &'static str = "Jurassic Jigsaw";
+
+    fn parse(&self, text: &str) -> Result<Vec<Tile>, Box<dyn error::Error>> {
+        Ok(parse_tiles(text)?)
+    }
+
+    fn part1(&self, input: &Vec<Tile>) -> Answer {
+        Answer::from(part1(&assemble(input)))
+    }
+
+    fn part2(&self, input: &Vec<Tile>) -> Answer {
+        Answer::from(part2(&assemble(input)))
+    }
 }
@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_20::Day20, "input/day20-input.txt");
+}
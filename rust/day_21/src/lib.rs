@@ -1,16 +1,19 @@
 
+//! Day 21: Allergen Assessment.
+
 use itertools::Itertools;
 use lazy_static::lazy_static;
 use regex::Regex;
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::io::BufRead;
-use std::io::BufReader;
-use std::fs::File;
+use std::error;
+
+use aoc_common::{InputError, Line};
+use aoc_solution::{Answer, Solution};
 
 /// Information extracted from a label
 #[derive(Debug, Eq, PartialEq)]
-struct Label {
+pub struct Label {
     /// All of the ingredients listed on the label
     ingredients: HashSet<String>,
 
@@ -18,23 +21,23 @@ struct Label {
     warnings: HashSet<String>,
 }
 
+lazy_static! {
+    static ref PATTERN: Regex = Regex::new(r"^(.*) \(contains (.*)\)$").expect("label regex");
+}
+
 /// Parses one input line, containing information from one label
-fn parse_label(text: &str) -> Label {
-    lazy_static! {
-        static ref PATTERN: Regex = Regex::new(r"^(.*) \(contains (.*)\)$").expect("label regex");
-    }
-    let captures = PATTERN.captures(text).unwrap();
-    let ingredients = captures[1].split(" ").map(|s| String::from(s)).collect();
-    let warnings = captures[2].split(", ").map(|s| String::from(s)).collect();
-    Label { ingredients, warnings }
+fn parse_label(line: &Line) -> Result<Label, InputError> {
+    let captures = PATTERN.captures(line.text).ok_or_else(|| {
+        line.invalid(format!("expected ingredients followed by (contains ...), found {:?}", line.text))
+    })?;
+    let ingredients = captures[1].split(" ").map(String::from).collect();
+    let warnings = captures[2].split(", ").map(String::from).collect();
+    Ok(Label { ingredients, warnings })
 }
 
 /// Parses an input file
-fn parse_input(file_name: &str) -> Vec<Label> {
-    let file = File::open(file_name).unwrap();
-    BufReader::new(file).lines()
-        .map(|line| parse_label(&line.unwrap()))
-        .collect()
+fn parse_input(text: &str) -> Result<Vec<Label>, InputError> {
+    aoc_common::lines(text).map(|line| parse_label(&line)).collect()
 }
 
 #[test]
@@ -50,14 +53,18 @@ fn test_parse_label() {
     warnings.insert(String::from("fish"));
 
     assert_eq!{
-        parse_label("mxmxvkd kfcds sqjhc nhms (contains dairy, fish)"),
+        parse_label(&Line { number: 1, text: "mxmxvkd kfcds sqjhc nhms (contains dairy, fish)" }).unwrap(),
         Label { ingredients, warnings }
     }
+    assert_eq!(
+        parse_input("a b (contains c)\na b\n").unwrap_err().to_string(),
+        "line 2: expected ingredients followed by (contains ...), found \"a b\""
+    );
 }
 
 /// Finds the next assignment of ingredient to allergen
-fn find_unique_ingredient<'a, 'b>(
-    remaining_ingredients: &'a HashSet<&'b str>,
+fn find_unique_ingredient<'b>(
+    remaining_ingredients: &HashSet<&'b str>,
     allergen: &str,
     labels: &'b [Label]
 ) -> Option<&'b str> {
@@ -65,15 +72,11 @@ fn find_unique_ingredient<'a, 'b>(
     // The ingredients that could have the allergen.  Until
     // we see a label with the allergen, the candidates are
     // ALL of the remaining ingredients.
-    let mut candidates: HashSet<&str> = remaining_ingredients.iter().map(|&s| s).collect();
+    let mut candidates: HashSet<&str> = remaining_ingredients.iter().copied().collect();
 
     for label in labels {
         if label.warnings.contains(allergen) {
-            candidates = 
-                candidates.iter()
-                    .filter(|&&ingr| label.ingredients.contains(ingr))
-                    .map(|&s| s)
-                    .collect();
+            candidates.retain(|&ingr| label.ingredients.contains(ingr));
         }
     }
 
@@ -129,7 +132,7 @@ fn assign_allergens<'a>(labels: &'a [Label]) -> HashMap<String, String> {
 
 /// Counts the number of occurences of ingredients in the given set.
 fn part1(labels: &[Label]) -> usize {
-    let assignments = assign_allergens(&labels);
+    let assignments = assign_allergens(labels);
     labels.iter()
         // labels
         .flat_map(|label| label.ingredients.iter())
@@ -140,18 +143,35 @@ fn part1(labels: &[Label]) -> usize {
 }
 
 fn part2(labels: &[Label]) -> String {
-    let mut ingredients_and_allergens: Vec<_> = assign_allergens(&labels).into_iter().collect();
+    let mut ingredients_and_allergens: Vec<_> = assign_allergens(labels).into_iter().collect();
     ingredients_and_allergens.sort_by( |p1, p2| p1.1.cmp(&p2.1) );
-    let answer: String = ingredients_and_allergens.iter().map(|(ingr, _)| ingr.as_str()).intersperse(",").collect();
+    let answer: String = Itertools::intersperse(ingredients_and_allergens.iter().map(|(ingr, _)| ingr.as_str()), ",").collect();
     answer
 }
 
-fn main() {
-    let sample = parse_input("sample.txt");
-    println!("Sample part 1: {:?}", part1(&sample));
-    println!("Sample part 2: {:?}", part2(&sample));
+#[test]
+fn test_sample() {
+    let sample = parse_input(&aoc_common::read_file("sample.txt").unwrap()).unwrap();
+    assert_eq!(part1(&sample), 5);
+    assert_eq!(part2(&sample), "mxmxvkd,sqjhc,fvjkl");
+}
 
-    let input = parse_input("input.txt");
-    println!("Part 1: {:?}", part1(&input));  // 1882
-    println!("Part 2: {:?}", part2(&input));  // xgtj,ztdctgq,bdnrnx,cdvjp,jdggtft,mdbq,rmd,lgllb
+pub struct Day21;
+
+impl Solution for Day21 {
+    type Input = Vec<Label>;
+    const DAY: u32 = 21;
+    const TITLE: &'static str = "Allergen Assessment";
+
+    fn parse(&self, text: &str) -> Result<Vec<Label>, Box<dyn error::Error>> {
+        Ok(parse_input(text)?)
+    }
+
+    fn part1(&self, input: &Vec<Label>) -> Answer {
+        Answer::from(part1(input))
+    }
+
+    fn part2(&self, input: &Vec<Label>) -> Answer {
+        Answer::from(part2(input))
+    }
 }
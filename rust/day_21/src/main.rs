@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_21::Day21, "input/day21-input.txt");
+}
@@ -1,7 +1,12 @@
 
+//! Day 22: Crab Combat.
+
 use std::collections::HashSet;
 use std::collections::VecDeque;
-use std::fs::read_to_string;
+use std::error;
+
+use aoc_common::{InputError, Section};
+use aoc_solution::{Answer, Solution};
 
 /// Cards are non-negative integers
 type Card = usize;
@@ -20,7 +25,7 @@ use crate::Player::*;
 
 /// The state of a game is the two decks
 #[derive(Clone, Debug, Eq, Hash, PartialEq)]
-struct Game(Deck, Deck);
+pub struct Game(Deck, Deck);
 
 impl Game {
     fn winner(&self) -> Player {
@@ -33,7 +38,7 @@ impl Game {
         }
     }
 
-    fn winning_deck<'a>(&'a self) -> &'a Deck {
+    fn winning_deck(&self) -> &Deck {
         if ! self.0.is_empty() && self.1.is_empty() {
             &self.0
         } else if self.0.is_empty() && ! self.1.is_empty() {
@@ -47,20 +52,26 @@ impl Game {
 /// Parses one deck, which has a header line (ignored) saying which 
 /// plaper it is, followed by one card per line, with the top of
 /// the deck first.
-fn parse_deck(text: &str) -> Deck {
-    text.split("\n")
-        .skip(1)
-        .filter(|t| ! t.is_empty())
-        .map(|t| t.parse::<Card>().unwrap())
-        .collect()
+fn parse_deck(section: &Section) -> Result<Deck, InputError> {
+    section.rest().map(|line| line.parse::<Card>()).collect()
 }
 
-/// Reads an input file containing two decks
+/// Parses an input file containing two decks
+fn parse_game(text: &str) -> Result<Game, InputError> {
+    let sections = aoc_common::sections_exactly(text, 2)?;
+    Ok(Game(parse_deck(&sections[0])?, parse_deck(&sections[1])?))
+}
+
+#[cfg(test)]
 fn read_input(file_name: &str) -> Game {
-    let text = read_to_string(file_name).unwrap();
-    let parts: Vec<_> = text.split("\n\n").collect();
-    assert_eq!(parts.len(), 2);
-    Game(parse_deck(parts[0]), parse_deck(parts[1]))
+    parse_game(&aoc_common::read_file(file_name).unwrap()).unwrap()
+}
+
+#[test]
+fn test_parse_game() {
+    assert_eq!(parse_game("Player 1:\n9\n2\n\nPlayer 2:\n5\n").unwrap(), Game(Deck::from(vec![9, 2]), Deck::from(vec![5])));
+    assert_eq!(parse_game("Player 1:\n9\n\nPlayer 2:\nfive\n").unwrap_err().to_string(), "line 5: expected a number, found \"five\"");
+    assert_eq!(parse_game("Player 1:\n9\n").unwrap_err().to_string(), "expected 2 sections separated by blank lines, found 1");
 }
 
 /// Plays a game, returning the state when the game is done.
@@ -95,7 +106,7 @@ fn play_recursive_game(game: &mut Game) -> Player {
     // play until done
     while ! game.0.is_empty() && ! game.1.is_empty()  {
         // first rule: if we've seen this state before, player 1 wins
-        if history.contains(&game) {
+        if history.contains(game) {
             return Player1;
         }
         history.insert(game.clone());
@@ -109,8 +120,8 @@ fn play_recursive_game(game: &mut Game) -> Player {
             if card1 <= game.0.len() && card2 <= game.1.len() {
                 // there are enough cards to play a recursive game
                 let mut inner_game = Game(
-                    game.0.iter().take(card1).map(|&c| c).collect(),
-                    game.1.iter().take(card2).map(|&c| c).collect()
+                    game.0.iter().take(card1).copied().collect(),
+                    game.1.iter().take(card2).copied().collect()
                 );
                 play_recursive_game(&mut inner_game)
             } else {
@@ -150,27 +161,44 @@ fn score_winner(deck: &Deck) -> usize {
     result
 }
 
-fn run_part2(label: &str, file_name: &str) {
-    let mut game = read_input(file_name);
+fn run_part1(game: &Game) -> usize {
+    score_winner(play_game(game).winning_deck())
+}
+
+fn run_part2(start: &Game) -> usize {
+    let mut game = start.clone();
     let winner = play_recursive_game(&mut game);
     let winning_deck =
         match winner {
             Player1 => &game.0,
             Player2 => &game.1,
         };
-    let score = score_winner(winning_deck);
-    println!("{}: {:?}", label, score);
+    score_winner(winning_deck)
 }
 
-fn main() {
+#[test]
+fn test_sample() {
     let sample = read_input("sample.txt");
-    let finished_sample = play_game(&sample);
-    println!("{:?}", finished_sample);
-    println!("{:?}", score_winner(finished_sample.winning_deck()));
+    assert_eq!(run_part1(&sample), 306);
+    assert_eq!(run_part2(&sample), 291);
+}
+
+pub struct Day22;
+
+impl Solution for Day22 {
+    type Input = Game;
+    const DAY: u32 = 22;
+    const TITLE: &'static str = "Crab Combat";
 
-    let input = read_input("input.txt");
-    println!("Part 1: {:?}", score_winner(play_game(&input).winning_deck()));  // 35370
+    fn parse(&self, text: &str) -> Result<Game, Box<dyn error::Error>> {
+        Ok(parse_game(text)?)
+    }
+
+    fn part1(&self, input: &Game) -> Answer {
+        Answer::from(run_part1(input))
+    }
 
-    run_part2("Part 2 sample", "sample.txt");
-    run_part2("Part 2", "input.txt");  // 36246
+    fn part2(&self, input: &Game) -> Answer {
+        Answer::from(run_part2(input))
+    }
 }
@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_22::Day22, "input/day22-input.txt");
+}
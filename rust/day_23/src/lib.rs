@@ -1,17 +1,23 @@
 
+//! Day 23: Crab Cups.
+
 use std::collections::HashMap;
+use std::error;
 use std::fmt;
 use std::fmt::Debug;
 use std::hash::Hash;
 use std::iter::FromIterator;
 
+use aoc_common::{ErrorKind, InputError};
+use aoc_solution::{Answer, Solution};
+
 /// A circle of things.
 /// 
 /// This implementation keeps two copies of each thing,
 /// and expects the things to have the Copy trait.
 /// 
 #[derive(Clone)]
-struct Ring<T: Copy + Debug + Eq + Hash> {
+pub struct Ring<T: Copy + Debug + Eq + Hash> {
 
     // Map from one item to the thing on its right.
     // Always exactly one entry for each thing in the ring.
@@ -35,7 +41,7 @@ impl<T: Copy + Debug + Eq + Hash> Ring<T> {
     // Adds the first item to the ring, which becomes
     // the current item.
     fn add_first(&mut self, item: T) {
-        assert!(self.current == None);
+        assert!(self.current.is_none());
         self.right.insert(item, item);
         self.current = Some(item);
     }
@@ -71,7 +77,7 @@ impl<T: Copy + Debug + Eq + Hash> Ring<T> {
 
     // Returns an iterator over the things in the ring, starting
     // with the current item.
-    fn iter<'a>(&'a self) -> RingIterator<'_, T> {
+    fn iter(&self) -> RingIterator<'_, T> {
         RingIterator {
             ring: self,
             start: self.current,
@@ -133,7 +139,7 @@ impl<'a, T: Copy + Debug + Hash + Eq> Iterator for RingIterator<'a, T> {
 #[test]
 fn test_ring_iter() {
     let vec: Vec<usize> = vec![2, 3, 5, 7];
-    let ring: Ring<usize> = vec.iter().map(|&n| n).collect();
+    let ring: Ring<usize> = vec.iter().copied().collect();
     let new_vec: Vec<usize> = ring.iter().collect();
     assert_eq!(vec, new_vec);
 }
@@ -163,7 +169,7 @@ fn one_step(ring: &mut Ring<usize>) {
     let c = ring.remove_right(current);
 
     // pick the destination cup
-    let destination = pick_destination(&ring);
+    let destination = pick_destination(ring);
 
     // add the three cups picked up after the destination
     ring.add_right(c, destination);
@@ -201,16 +207,52 @@ fn run_part2(start: &Ring<usize>) -> usize {
     a * b
 }
 
-fn main() {
+#[test]
+fn test_sample() {
     let sample = ring_from_str("389125467");
     assert_eq!(run_part1(&sample), "67384529");
-    
-    let input = ring_from_str("952438716");
-    let part1_answer = run_part1(&input);
-    assert_eq!(part1_answer, "97342568");  // verified on web site
-    println!("Part 1: {:?}", part1_answer);
-
-    let part2_answer = run_part2(&input);
-    assert_eq!(part2_answer, 902208073192);  // verified on web site
-    println!("Part 2: {:?}", part2_answer);
+}
+
+/// Parses the labels of the cups, in order around the circle, checking
+/// that each of 1 through 9 is there once.
+fn parse_labels(text: &str) -> Result<Ring<usize>, InputError> {
+    let line = aoc_common::lines(text).next().ok_or_else(|| InputError::new(0, ErrorKind::Missing(String::from("cup labels"))))?;
+    let labels = line.text.trim();
+    let mut sorted: Vec<char> = labels.chars().collect();
+    sorted.sort();
+    if sorted.into_iter().collect::<String>() != "123456789" {
+        return Err(line.invalid(format!("expected the cups 1 through 9 in some order, found {:?}", labels)));
+    }
+    Ok(ring_from_str(labels))
+}
+
+#[test]
+fn test_parse_labels() {
+    assert_eq!(parse_labels("389125467\n").unwrap().iter().collect::<Vec<usize>>(), vec![3, 8, 9, 1, 2, 5, 4, 6, 7]);
+    assert_eq!(
+        parse_labels("\n38912546\n").unwrap_err().to_string(),
+        "line 2: expected the cups 1 through 9 in some order, found \"38912546\""
+    );
+    assert_eq!(parse_labels("").unwrap_err().to_string(), "missing cup labels");
+}
+
+pub struct Day23;
+
+impl Solution for Day23 {
+    type Input = Ring<usize>;
+    const DAY: u32 = 23;
+    const TITLE: &'static str = "Crab Cups";
+
+    /// The input is the labels of the cups, in order around the circle.
+    fn parse(&self, text: &str) -> Result<Ring<usize>, Box<dyn error::Error>> {
+        Ok(parse_labels(text)?)
+    }
+
+    fn part1(&self, input: &Ring<usize>) -> Answer {
+        Answer::from(run_part1(input))
+    }
+
+    fn part2(&self, input: &Ring<usize>) -> Answer {
+        Answer::from(run_part2(input))
+    }
 }
@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_23::Day23, "input/day23-input.txt");
+}
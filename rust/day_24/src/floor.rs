@@ -0,0 +1,224 @@
+//! Reading the directions to the tiles, and flipping them.
+//!
+//! Each line of the input is a list of directions, with no spaces
+//! between them, that leads from the reference tile to the tile to
+//! flip.  The tiles flipped an odd number of times end up black.
+
+use std::collections;
+use std::fmt;
+use std::iter;
+
+use crate::hex::{self, Pos};
+
+fn make_dir_to_pos() -> collections::HashMap<String, Pos> {
+    hex::Direction::ALL.iter().map(|d| (String::from(d.name()), d.offset())).collect()
+}
+
+#[test]
+fn test_directions_are_hex_neighbors() {
+    let mut directions: Vec<Pos> = make_dir_to_pos().values().cloned().collect();
+    directions.sort();
+    assert_eq!(directions, conway_life::neighborhood::hexagonal::<Pos>(1));
+}
+
+/// A token that isn't a direction, at a byte offset in a line.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct BadToken {
+    pub offset: usize,
+    /// The character that was wrong, or None if the line ended
+    /// in the middle of a direction.
+    pub found: Option<char>,
+    /// True if the bad character came after an "n" or "s".
+    pub after_north_south: bool,
+}
+
+/// A line of the input file with a bad direction in it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BadLine {
+    /// The line number, starting at 1.
+    pub line: usize,
+    pub token: BadToken,
+}
+
+impl fmt::Display for BadLine {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}, byte {}: ", self.line, self.token.offset)?;
+        if self.token.after_north_south {
+            write!(f, "expected e or w after n or s, found ")?;
+        } else {
+            write!(f, "expected a direction, found ")?;
+        }
+        match self.token.found {
+            Some(c) => write!(f, "{:?}", c),
+            None => write!(f, "end of line"),
+        }
+    }
+}
+
+/// Structure backing `repeated_regex`
+struct RepeatedRegex<'a> {
+    /// The pattern to keep matching in the string
+    pattern: &'a regex::Regex,
+    /// The remaining string to match.  After the first
+    /// match, this is the part of the string after the 
+    /// previous match.
+    remaining: &'a str,
+    /// The byte offset of `remaining` in the whole string.
+    offset: usize,
+}
+
+/// Iterator backing `repeated_regex`.
+/// 
+/// The `next()` function finds the next match of the pattern
+/// in the remaining string.  If the pattern doesn't match, it
+/// returns the offset where matching failed, and then stops.
+impl<'a> Iterator for RepeatedRegex<'a> {
+    type Item = Result<&'a str, usize>;
+
+    fn next(&mut self) -> Option<Result<&'a str, usize>> {
+        if self.remaining.is_empty() {
+            None
+        } else {
+            match self.pattern.find(self.remaining) {
+                Some(whole_match) => {
+                    self.remaining = & self.remaining[whole_match.end()..];
+                    self.offset += whole_match.end();
+                    Some(Ok(whole_match.as_str()))
+                },
+                None => {
+                    self.remaining = "";
+                    Some(Err(self.offset))
+                },
+            }
+        }
+    }
+}
+
+/// Returns an iteraror of the strings that match the given pattern
+/// in a haystack.  If the pattern starts with `^`, the result is guaranteed
+/// to cover every character of the haystack, or end with an error
+/// giving the offset of the first character not covered.
+fn repeated_regex<'a>(pattern: &'a regex::Regex, haystack: &'a str) -> RepeatedRegex<'a> {
+    RepeatedRegex { pattern, remaining: haystack, offset: 0 }
+}
+
+/// Adds up the directions in one line of the input.
+pub fn parse_directions(directions: &str) -> Result<Pos, BadToken> {
+    lazy_static::lazy_static! {
+        static ref PATTERN: regex::Regex = regex::Regex::new(r"^[ns]?[ew]").unwrap();
+    }
+    let dir_to_pos = make_dir_to_pos();
+    repeated_regex(&PATTERN, directions)
+        .map(|token| {
+            token
+                .map(|dir| dir_to_pos[dir])
+                .map_err(|offset| {
+                    let rest = &directions[offset..];
+                    if rest.starts_with('n') || rest.starts_with('s') {
+                        BadToken { offset: offset + 1, found: rest[1..].chars().next(), after_north_south: true }
+                    } else {
+                        BadToken { offset, found: rest.chars().next(), after_north_south: false }
+                    }
+                })
+        })
+        .sum()
+}
+
+#[test]
+fn test_parse_directions() {
+    assert_eq!(parse_directions("nwwswee"), Ok(hex::pos(0, 0)));
+    assert_eq!(parse_directions("esew"), Ok(hex::pos(1, -1)));
+    assert_eq!(
+        parse_directions("nwwxee"),
+        Err(BadToken { offset: 3, found: Some('x'), after_north_south: false })
+    );
+    assert_eq!(
+        parse_directions("enq"),
+        Err(BadToken { offset: 2, found: Some('q'), after_north_south: true })
+    );
+    assert_eq!(
+        parse_directions("ees"),
+        Err(BadToken { offset: 3, found: None, after_north_south: true })
+    );
+}
+
+/// Flips the tiles for all of the lines of the input.  If any lines
+/// are bad, returns all of them.
+pub fn tiles_from_text(text: &str) -> Result<collections::HashSet<Pos>, Vec<BadLine>> {
+    let mut black_tiles: collections::HashSet<Pos> = collections::HashSet::new();
+    let mut bad_lines = Vec::new();
+    for line in aoc_common::lines(text) {
+        match parse_directions(line.text) {
+            Ok(p) => {
+                if black_tiles.contains(&p) {
+                    black_tiles.remove(&p);
+                } else {
+                    black_tiles.insert(p);
+                }
+            },
+            Err(token) => bad_lines.push(BadLine { line: line.number, token }),
+        }
+    }
+    if bad_lines.is_empty() { Ok(black_tiles) } else { Err(bad_lines) }
+}
+
+#[test]
+fn test_tiles_from_text() {
+    assert_eq!(tiles_from_text("e\nw\ne\n").unwrap().len(), 1);
+    let errors = tiles_from_text("e\nnwz\nw\nsee\nsx\n").unwrap_err();
+    let messages: Vec<String> = errors.iter().map(|e| e.to_string()).collect();
+    assert_eq!(
+        messages,
+        vec![
+            "line 2, byte 2: expected a direction, found 'z'",
+            "line 5, byte 1: expected e or w after n or s, found 'x'",
+        ]
+    );
+}
+
+/// The number of black tiles after the living art has run for the
+/// given number of days.
+pub fn population_after(tiles: &collections::HashSet<Pos>, days: usize) -> usize {
+    let neighbors: Vec<Pos> = conway_life::neighborhood::hexagonal(1);
+    let rule: conway_life::LifeRule = "B2/S12".parse().unwrap();
+    let (_, history) = conway_life::cycle::run(tiles, neighbors.as_slice(), &rule, days);
+    history.population_at(days).unwrap()
+}
+
+/// The floor on each day of the living art, starting with day 0 and
+/// ending with the given day.  Each floor is made only when it is
+/// asked for.
+pub fn floors(tiles: collections::HashSet<Pos>, days: usize) -> impl Iterator<Item = collections::HashSet<Pos>> {
+    let neighbors: Vec<Pos> = conway_life::neighborhood::hexagonal(1);
+    let rule: conway_life::LifeRule = "B2/S12".parse().unwrap();
+    iter::successors(Some(tiles), move |floor| Some(conway_life::conway_step(floor, &neighbors, &rule)))
+        .take(days + 1)
+}
+
+/// The black tiles after the living art has run for the given number
+/// of days.  Only the current floor is kept as it runs.
+pub fn floor_after(tiles: collections::HashSet<Pos>, days: usize) -> collections::HashSet<Pos> {
+    floors(tiles, days).last().unwrap()
+}
+
+/// Runs the living art for the given number of days, starting from
+/// the tiles flipped in part 1.  Returns the floor on every day,
+/// starting with day 0.
+pub fn living_art(tiles: collections::HashSet<Pos>, days: usize) -> Vec<collections::HashSet<Pos>> {
+    let neighbors: Vec<Pos> = conway_life::neighborhood::hexagonal(1);
+    let rule: conway_life::LifeRule = "B2/S12".parse().unwrap();
+    let mut result = vec![tiles];
+    for day in 0..days {
+        result.push(conway_life::conway_step(&result[day], &neighbors, &rule));
+    }
+    result
+}
+
+#[test]
+fn test_floor_after() {
+    let tiles = tiles_from_text("e\nne\nw\nsw\nse\n").unwrap();
+    let history = living_art(tiles.clone(), 5);
+    assert_eq!(floor_after(tiles.clone(), 5), history[5]);
+    assert_eq!(floors(tiles.clone(), 5).collect::<Vec<_>>(), history);
+    assert_eq!(population_after(&tiles, 5), history[5].len());
+}
@@ -0,0 +1,360 @@
+//! Positions on a grid of hexagons.
+//!
+//! `Pos` uses axial coordinates: x increases to the east, and y
+//! increases to the northeast.  The six directions are:
+//!
+//! ```text
+//!    nw (-1, 1)   ne (0, 1)
+//! w (-1, 0)           e (1, 0)
+//!    sw (0, -1)   se (1, -1)
+//! ```
+//!
+//! Cube coordinates add a third coordinate, z = -x - y, which makes
+//! distances and rotations symmetric.  Offset coordinates number the
+//! hexes in rows and columns, with each odd row shifted half a hex
+//! to the east, which is handy for drawing and for storing a floor
+//! in a 2-D array.
+//!
+//! The algorithms follow Red Blob Games' "Hexagonal Grids" page.
+
+use std::iter;
+use std::ops;
+
+/// A position on the hex grid, in axial coordinates.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Pos {
+    pub x: i32,
+    pub y: i32,
+}
+
+/// Shorthand for making a Pos.
+pub fn pos(x: i32, y: i32) -> Pos {
+    Pos { x, y }
+}
+
+impl ops::Add for Pos {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        pos(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl ops::Sub for Pos {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        pos(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl ops::Mul<i32> for Pos {
+    type Output = Self;
+
+    fn mul(self, factor: i32) -> Self {
+        pos(self.x * factor, self.y * factor)
+    }
+}
+
+impl iter::Sum for Pos {
+    fn sum<I>(iter: I) -> Self
+        where I: Iterator<Item = Pos>
+    {
+        iter.fold(pos(0, 0), |a, b| a + b)
+    }
+}
+
+#[test]
+fn test_add_pos() {
+    assert_eq!(pos(1, 2) + pos(4, 8), pos(5, 10));
+    assert_eq!(pos(1, 2) - pos(4, 8), pos(-3, -6));
+    assert_eq!(pos(1, -2) * 3, pos(3, -6));
+}
+
+/// Axial hex coordinates are a 2-D grid, so they can use the
+/// bounded and toroidal board topologies.
+impl conway_life::Planar for Pos {
+    fn x(&self) -> i32 {
+        self.x
+    }
+
+    fn y(&self) -> i32 {
+        self.y
+    }
+
+    fn from_xy(x: i32, y: i32) -> Pos {
+        pos(x, y)
+    }
+}
+
+impl conway_life::Vector for Pos {
+    const DIMS: usize = 2;
+
+    fn coord(&self, axis: usize) -> i32 {
+        [self.x, self.y][axis]
+    }
+
+    fn from_coords(coords: &[i32]) -> Pos {
+        pos(coords[0], coords[1])
+    }
+}
+
+#[test]
+fn test_hex_topologies() {
+    use conway_life::topology::{Bounded, Rectangle, Torus};
+    use std::collections;
+
+    let torus = Torus(Rectangle::new(0, 0, 4, 4));
+    assert_eq!(conway_life::Topology::normalize(&torus, pos(-1, 5)), Some(pos(3, 1)));
+
+    // Two adjacent black tiles have two common neighbors, which both
+    // turn black, unless one is past the wall.
+    let neighbors: Vec<Pos> = conway_life::neighborhood::hexagonal(1);
+    let rule: conway_life::LifeRule = "B2/S12".parse().unwrap();
+    let tiles: collections::HashSet<Pos> = [pos(0, 0), pos(1, 0)].iter().cloned().collect();
+    assert_eq!(
+        conway_life::conway_step(&tiles, &neighbors, &rule),
+        [pos(0, 0), pos(1, 0), pos(0, 1), pos(1, -1)].iter().cloned().collect()
+    );
+    let bounded = Bounded(Rectangle::new(0, 0, 4, 4));
+    assert_eq!(
+        conway_life::conway_step_in(&tiles, &neighbors, &rule, &bounded),
+        [pos(0, 0), pos(1, 0), pos(0, 1)].iter().cloned().collect()
+    );
+}
+
+/// The six directions from a hex to its neighbors.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Direction {
+    E,
+    NE,
+    NW,
+    W,
+    SW,
+    SE,
+}
+
+impl Direction {
+    /// All of the directions, going counterclockwise from east.
+    pub const ALL: [Direction; 6] =
+        [Direction::E, Direction::NE, Direction::NW, Direction::W, Direction::SW, Direction::SE];
+
+    /// The change in position from moving one hex this way.
+    pub fn offset(self) -> Pos {
+        match self {
+            Direction::E => pos(1, 0),
+            Direction::NE => pos(0, 1),
+            Direction::NW => pos(-1, 1),
+            Direction::W => pos(-1, 0),
+            Direction::SW => pos(0, -1),
+            Direction::SE => pos(1, -1),
+        }
+    }
+
+    /// The name used in the puzzle input, like "ne".
+    pub fn name(self) -> &'static str {
+        match self {
+            Direction::E => "e",
+            Direction::NE => "ne",
+            Direction::NW => "nw",
+            Direction::W => "w",
+            Direction::SW => "sw",
+            Direction::SE => "se",
+        }
+    }
+}
+
+/// A position in cube coordinates, where x + y + z is always zero.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Cube {
+    pub x: i32,
+    pub y: i32,
+    pub z: i32,
+}
+
+/// A position as a row and column, with odd rows shifted half a hex
+/// to the east.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Offset {
+    pub col: i32,
+    pub row: i32,
+}
+
+impl Cube {
+    pub fn to_axial(self) -> Pos {
+        pos(self.x, self.y)
+    }
+
+    /// Finds the hex that holds a point given in fractional cube
+    /// coordinates, by rounding each coordinate and then fixing the
+    /// one that changed the most so they still add up to zero.
+    fn round(x: f64, y: f64, z: f64) -> Cube {
+        let (mut rx, mut ry, mut rz) = (x.round(), y.round(), z.round());
+        let (dx, dy, dz) = ((rx - x).abs(), (ry - y).abs(), (rz - z).abs());
+        if dy < dx && dz < dx {
+            rx = -ry - rz;
+        } else if dz < dy {
+            ry = -rx - rz;
+        } else {
+            rz = -rx - ry;
+        }
+        Cube { x: rx as i32, y: ry as i32, z: rz as i32 }
+    }
+}
+
+impl Offset {
+    pub fn to_axial(self) -> Pos {
+        pos(self.col - self.row.div_euclid(2), self.row)
+    }
+}
+
+impl Pos {
+    pub fn to_cube(self) -> Cube {
+        Cube { x: self.x, y: self.y, z: -self.x - self.y }
+    }
+
+    pub fn to_offset(self) -> Offset {
+        Offset { col: self.x + self.y.div_euclid(2), row: self.y }
+    }
+
+    /// The neighbor in the given direction.
+    pub fn neighbor(self, direction: Direction) -> Pos {
+        self + direction.offset()
+    }
+
+    /// All six neighbors, going counterclockwise from east.
+    pub fn neighbors(self) -> Vec<Pos> {
+        Direction::ALL.iter().map(|&d| self.neighbor(d)).collect()
+    }
+
+    /// The number of steps from here to the other hex.
+    pub fn distance(self, other: Pos) -> i32 {
+        let d = (self - other).to_cube();
+        (d.x.abs() + d.y.abs() + d.z.abs()) / 2
+    }
+
+    /// Turns 60 degrees counterclockwise around the origin, so east
+    /// becomes northeast.
+    pub fn rotate_ccw(self) -> Pos {
+        let c = self.to_cube();
+        pos(-c.y, -c.z)
+    }
+
+    /// Turns 60 degrees clockwise around the origin, so east becomes
+    /// southeast.
+    pub fn rotate_cw(self) -> Pos {
+        let c = self.to_cube();
+        pos(-c.z, -c.x)
+    }
+
+    /// Flips across the east-west line through the origin, so
+    /// northeast becomes southeast.
+    pub fn reflect(self) -> Pos {
+        pos(self.x + self.y, -self.y)
+    }
+}
+
+/// The hexes exactly `radius` steps from the center, going
+/// counterclockwise from the one `radius` steps to the southwest.
+pub fn ring(center: Pos, radius: i32) -> Vec<Pos> {
+    if radius == 0 {
+        return vec![center];
+    }
+    let mut result = Vec::new();
+    let mut p = center + Direction::SW.offset() * radius;
+    for &direction in Direction::ALL.iter() {
+        for _ in 0..radius {
+            result.push(p);
+            p = p.neighbor(direction);
+        }
+    }
+    result
+}
+
+/// The hexes within `radius` steps of the center: the center, then
+/// each ring going outward.
+pub fn spiral(center: Pos, radius: i32) -> Vec<Pos> {
+    (0..=radius).flat_map(|r| ring(center, r)).collect()
+}
+
+/// The hexes on a straight line from one hex to another, including
+/// both ends.  Each one is a neighbor of the one before.
+pub fn line(from: Pos, to: Pos) -> Vec<Pos> {
+    let n = from.distance(to);
+    let (a, b) = (from.to_cube(), to.to_cube());
+    // Nudging the ends keeps points that fall exactly on an edge
+    // between two hexes from rounding different ways.
+    let (ax, ay, az) = (a.x as f64 + 1e-6, a.y as f64 + 1e-6, a.z as f64 - 2e-6);
+    let (bx, by, bz) = (b.x as f64 + 1e-6, b.y as f64 + 1e-6, b.z as f64 - 2e-6);
+    (0..=n)
+        .map(|i| {
+            let t = if n == 0 { 0.0 } else { i as f64 / n as f64 };
+            Cube::round(ax + (bx - ax) * t, ay + (by - ay) * t, az + (bz - az) * t).to_axial()
+        })
+        .collect()
+}
+
+#[test]
+fn test_coordinates() {
+    assert_eq!(pos(2, -3).to_cube(), Cube { x: 2, y: -3, z: 1 });
+    assert_eq!(Cube { x: 2, y: -3, z: 1 }.to_axial(), pos(2, -3));
+    assert_eq!(pos(0, 1).to_offset(), Offset { col: 0, row: 1 });
+    assert_eq!(pos(-1, 2).to_offset(), Offset { col: 0, row: 2 });
+    assert_eq!(pos(0, -1).to_offset(), Offset { col: -1, row: -1 });
+    for x in -5..=5 {
+        for y in -5..=5 {
+            assert_eq!(pos(x, y).to_offset().to_axial(), pos(x, y));
+        }
+    }
+}
+
+#[test]
+fn test_neighbors_and_distance() {
+    assert_eq!(pos(0, 0).neighbors(), vec![pos(1, 0), pos(0, 1), pos(-1, 1), pos(-1, 0), pos(0, -1), pos(1, -1)]);
+    assert_eq!(pos(3, 4).neighbor(Direction::NW), pos(2, 5));
+    assert_eq!(pos(0, 0).distance(pos(0, 0)), 0);
+    assert_eq!(pos(0, 0).distance(pos(1, -1)), 1);
+    assert_eq!(pos(0, 0).distance(pos(3, -1)), 3);
+    assert_eq!(pos(0, 0).distance(pos(2, 2)), 4);
+    assert_eq!(pos(-2, 3).distance(pos(1, -1)), 4);
+    assert!(pos(1, 1).neighbors().iter().all(|&n| n.distance(pos(1, 1)) == 1));
+}
+
+#[test]
+fn test_rings_and_spirals() {
+    assert_eq!(ring(pos(5, 5), 0), vec![pos(5, 5)]);
+    assert_eq!(ring(pos(0, 0), 1), vec![pos(0, -1), pos(1, -1), pos(1, 0), pos(0, 1), pos(-1, 1), pos(-1, 0)]);
+    for radius in 1..5 {
+        let r = ring(pos(2, -1), radius);
+        assert_eq!(r.len(), 6 * radius as usize);
+        assert!(r.iter().all(|p| p.distance(pos(2, -1)) == radius));
+    }
+    let s = spiral(pos(0, 0), 3);
+    assert_eq!(s.len(), 1 + 3 * 3 * 4);
+    let unique: std::collections::HashSet<Pos> = s.iter().cloned().collect();
+    assert_eq!(unique.len(), s.len());
+}
+
+#[test]
+fn test_line() {
+    assert_eq!(line(pos(1, 1), pos(1, 1)), vec![pos(1, 1)]);
+    assert_eq!(line(pos(0, 0), pos(3, 0)), vec![pos(0, 0), pos(1, 0), pos(2, 0), pos(3, 0)]);
+    let l = line(pos(-2, 0), pos(3, 2));
+    assert_eq!(l.len(), 8);
+    assert_eq!(l[0], pos(-2, 0));
+    assert_eq!(l[7], pos(3, 2));
+    assert!(l.windows(2).all(|w| w[0].distance(w[1]) == 1));
+}
+
+#[test]
+fn test_rotate_and_reflect() {
+    assert_eq!(pos(1, 0).rotate_ccw(), pos(0, 1));
+    assert_eq!(pos(1, 0).rotate_cw(), pos(1, -1));
+    assert_eq!(pos(0, 1).reflect(), pos(1, -1));
+    assert_eq!(pos(-1, 1).reflect(), pos(0, -1));
+    let p = pos(3, -7);
+    assert_eq!(p.rotate_ccw().rotate_cw(), p);
+    assert_eq!((0..6).fold(p, |q, _| q.rotate_ccw()), p);
+    assert_eq!(p.reflect().reflect(), p);
+    assert_eq!(p.rotate_ccw().distance(pos(0, 0)), p.distance(pos(0, 0)));
+}
@@ -0,0 +1,39 @@
+//! Day 24: Lobby Layout.
+//!
+//! The floor of the lobby is tiled with hexagons.  The `hex` module
+//! has the coordinates and geometry of the hex grid, the `floor`
+//! module reads the input and flips the tiles, and the `svg` module
+//! draws the floor.
+
+use std::collections;
+use std::error;
+
+use aoc_solution::{Answer, Solution};
+
+pub mod floor;
+pub mod hex;
+pub mod svg;
+
+pub struct Day24;
+
+impl Solution for Day24 {
+    /// The black tiles after following the directions.
+    type Input = collections::HashSet<hex::Pos>;
+    const DAY: u32 = 24;
+    const TITLE: &'static str = "Lobby Layout";
+
+    fn parse(&self, text: &str) -> Result<collections::HashSet<hex::Pos>, Box<dyn error::Error>> {
+        floor::tiles_from_text(text).map_err(|bad_lines| {
+            let messages: Vec<String> = bad_lines.iter().map(|b| b.to_string()).collect();
+            messages.join("\n").into()
+        })
+    }
+
+    fn part1(&self, input: &collections::HashSet<hex::Pos>) -> Answer {
+        Answer::from(input.len())
+    }
+
+    fn part2(&self, input: &collections::HashSet<hex::Pos>) -> Answer {
+        Answer::from(floor::population_after(input, 100))
+    }
+}
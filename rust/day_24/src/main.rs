@@ -1,145 +1,165 @@
 
-use lazy_static;
-use regex;
 use std::collections;
-use std::iter;
+use std::env;
 use std::fs;
-use std::ops;
-
-use conway_life;
-
-#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
-struct Pos {
-    x: i32,
-    y: i32,
-}
-
-fn pos(x: i32, y: i32) -> Pos {
-    Pos { x, y }
-}
-
-impl ops::Add for Pos {
-    type Output = Self;
-
-    fn add(self, other: Self) -> Self {
-        pos(self.x + other.x, self.y + other.y)
-    }
-}
-
-impl iter::Sum for Pos {
-    fn sum<I>(iter: I) -> Self 
-        where I: Iterator<Item = Pos>
-    {
-        iter.fold(pos(0, 0), |a, b| a + b)
-    }
-}
-
-#[test]
-fn test_add_pos() {
-    assert_eq!(pos(1, 2) + pos(4, 8), pos(5, 10));
-}
-
-fn make_dir_to_pos() -> collections::HashMap<String, Pos> {
-    let mut result = collections::HashMap::new();
-    result.insert(String::from("w"), pos(-1, 0));
-    result.insert(String::from("nw"), pos(-1, 1));
-    result.insert(String::from("ne"), pos(0, 1));
-    result.insert(String::from("e"), pos(1, 0));
-    result.insert(String::from("se"), pos(1, -1));
-    result.insert(String::from("sw"), pos(0, -1));
-    result.insert(String::from("w"), pos(-1, 0));
-    result
+use std::io;
+use std::process;
+
+use day_24::floor::{self, living_art, tiles_from_text, BadLine};
+use day_24::hex::Pos;
+
+/// Why the tiles couldn't be read from a file.
+#[derive(Debug)]
+enum FloorError {
+    Io(io::Error),
+    BadLines(Vec<BadLine>),
+}
+
+/// Reads the input file and flips the tiles.  If any lines are bad,
+/// returns all of them.
+fn tiles_from_part1(file_name: &str) -> Result<collections::HashSet<Pos>, FloorError> {
+    let text = fs::read_to_string(file_name).map_err(FloorError::Io)?;
+    tiles_from_text(&text).map_err(FloorError::BadLines)
+}
+
+/// Reads the tiles, or prints what's wrong with the file and exits.
+fn tiles_or_exit(file_name: &str) -> collections::HashSet<Pos> {
+    tiles_from_part1(file_name).unwrap_or_else(|error| {
+        match error {
+            FloorError::Io(e) => eprintln!("{}: {}", file_name, e),
+            FloorError::BadLines(bad_lines) => {
+                for bad_line in bad_lines {
+                    eprintln!("{}: {}", file_name, bad_line);
+                }
+            },
+        }
+        process::exit(1);
+    })
 }
 
-/// Structure backing `repeated_regex`
-struct RepeatedRegex<'a> {
-    /// The pattern to keep matching in the string
-    pattern: &'a regex::Regex,
-    /// The remaining string to match.  After the first
-    /// match, this is the part of the string after the 
-    /// previous match.
-    remaining: &'a str,
+fn run_part1(file_name: &str) -> usize {
+    tiles_or_exit(file_name).len()
 }
 
-/// Iterator backing `repeated_regex`.
-/// 
-/// The `next()` function finds the next match of the pattern
-/// in the remaining string.
-impl<'a> Iterator for RepeatedRegex<'a> {
-    type Item = &'a str;
-
-    fn next(&mut self) -> Option<&'a str> {
-        if self.remaining.len() == 0 {
-            None
+fn run_part2(file_name: &str) -> usize {
+    floor::population_after(&tiles_or_exit(file_name), 100)
+}
+
+const USAGE: &str = "\
+usage: day_24 [COMMAND FILE [--days N]]
+
+With no command, prints the answers for the sample and the puzzle input.
+
+commands:
+  flip FILE              print the number of black tiles after flipping
+  run FILE               print the number of black tiles after the days
+  populations FILE       print the number of black tiles on each day
+  tiles FILE             print the x and y of each black tile after the days
+  svg FILE DIRECTORY     draw the floor on each day as SVG files
+
+--days N sets the number of days of living art, which is 100 if not given.";
+
+/// What the command line asks for.
+#[derive(Debug, Eq, PartialEq)]
+enum Command {
+    Answers,
+    Flip { file: String },
+    Run { file: String, days: usize },
+    Populations { file: String, days: usize },
+    Tiles { file: String, days: usize },
+    Svg { file: String, directory: String, days: usize },
+}
+
+/// Parses the command line arguments, not including the program name.
+fn parse_args(args: &[String]) -> Result<Command, String> {
+    let mut days = 100;
+    let mut words = Vec::new();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if arg == "--days" {
+            let value = iter.next().ok_or("--days needs a number")?;
+            days = value.parse().map_err(|_| format!("--days needs a number, not {:?}", value))?;
+        } else if arg.starts_with("--") {
+            return Err(format!("unknown option {:?}", arg));
         } else {
-            let cap = self.pattern.captures(self.remaining).unwrap();
-            let whole_match = cap.get(0).unwrap();
-            self.remaining = & self.remaining[whole_match.end()..];
-            Some(& whole_match.as_str())
+            words.push(arg.clone());
         }
     }
-}
-
-/// Returns an iteraror of the strings that match the given pattern
-/// in a haystack.  If the pattern starts with `^`, the result is guaranteed
-/// to cover every character of the haystack.
-/// 
-/// No proper error checking (yet).  Panics if the pattern is not found in the haystack.
-fn repeated_regex<'a>(pattern: &'a regex::Regex, haystack: &'a str) -> RepeatedRegex<'a> {
-    RepeatedRegex { pattern: pattern, remaining: haystack, }
-}
-
-fn parse_directions(directions: &str) -> Pos {
-    lazy_static::lazy_static! {
-        static ref PATTERN: regex::Regex = regex::Regex::new(r"^[ns]?[ew]").unwrap();
-    }
-    let dir_to_pos = make_dir_to_pos();
-    repeated_regex(&PATTERN, directions)
-        .map(|dir| *dir_to_pos.get(dir).unwrap())
-        .sum()
+    let command = match words.as_slice() {
+        [] => Command::Answers,
+        [c, file] if c == "flip" => Command::Flip { file: file.clone() },
+        [c, file] if c == "run" => Command::Run { file: file.clone(), days },
+        [c, file] if c == "populations" => Command::Populations { file: file.clone(), days },
+        [c, file] if c == "tiles" => Command::Tiles { file: file.clone(), days },
+        [c, file, directory] if c == "svg" => {
+            Command::Svg { file: file.clone(), directory: directory.clone(), days }
+        },
+        [c, ..] => return Err(format!("bad arguments for command {:?}", c)),
+    };
+    Ok(command)
 }
 
 #[test]
-fn test_parse_directions() {
-    assert_eq!(parse_directions("nwwswee"), pos(0, 0));
+fn test_parse_args() {
+    let args = |text: &str| -> Vec<String> { text.split_whitespace().map(String::from).collect() };
+    assert_eq!(parse_args(&args("")), Ok(Command::Answers));
+    assert_eq!(parse_args(&args("flip a.txt")), Ok(Command::Flip { file: String::from("a.txt") }));
+    assert_eq!(parse_args(&args("run a.txt")), Ok(Command::Run { file: String::from("a.txt"), days: 100 }));
+    assert_eq!(
+        parse_args(&args("populations --days 7 a.txt")),
+        Ok(Command::Populations { file: String::from("a.txt"), days: 7 })
+    );
+    assert_eq!(
+        parse_args(&args("svg a.txt out --days 3")),
+        Ok(Command::Svg { file: String::from("a.txt"), directory: String::from("out"), days: 3 })
+    );
+    assert_eq!(parse_args(&args("run a.txt --days")), Err(String::from("--days needs a number")));
+    assert_eq!(parse_args(&args("run a.txt --days x")), Err(String::from("--days needs a number, not \"x\"")));
+    assert_eq!(parse_args(&args("run a.txt --fast")), Err(String::from("unknown option \"--fast\"")));
+    assert_eq!(parse_args(&args("flip")), Err(String::from("bad arguments for command \"flip\"")));
+    assert_eq!(parse_args(&args("fly a.txt")), Err(String::from("bad arguments for command \"fly\"")));
 }
 
-fn tiles_from_part1(file_name: &str) -> collections::HashSet<Pos> {
-    let text = fs::read_to_string(file_name).unwrap();
-    let mut black_tiles: collections::HashSet<Pos> = collections::HashSet::new();
-    for line in text.split("\n") {
-        if ! line.is_empty() {
-            let p = parse_directions(line);
-            if black_tiles.contains(&p) {
-                black_tiles.remove(&p);
-            } else {
-                black_tiles.insert(p);
-            }
-        }
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if args.iter().any(|a| a == "--help" || a == "-h" || a == "help") {
+        println!("{}", USAGE);
+        return;
     }
-    black_tiles
-}
-
-fn run_part1(file_name: &str) -> usize {
-    tiles_from_part1(file_name).len()
-}
-
-fn is_alive_part2(was_alive: bool, neighbor_count: usize) -> bool {
-    return neighbor_count == 2 || (was_alive && neighbor_count == 1)
-}
-
-fn run_part2(file_name: &str) -> usize {
-    let neighbors: Vec<_> = make_dir_to_pos().iter().map(|(_, p)| *p).collect();
-    let mut tiles = tiles_from_part1(file_name);
-    for _ in 1..=100 {
-        tiles = conway_life::conway_step(&tiles, neighbors.as_slice(), is_alive_part2);
+    let command = parse_args(&args).unwrap_or_else(|message| {
+        eprintln!("day_24: {}\n\n{}", message, USAGE);
+        process::exit(2);
+    });
+    match command {
+        Command::Answers => {
+            println!("Part 1 sample: {}", run_part1("input/day24-sample.txt"));
+            println!("Part 2 sample: {}", run_part2("input/day24-sample.txt"));
+            println!("Part 1: {}", run_part1("input/day24-input.txt"));
+            println!("Part 2: {}", run_part2("input/day24-input.txt"));
+        },
+        Command::Flip { file } => println!("{}", run_part1(&file)),
+        Command::Run { file, days } => println!("{}", floor::population_after(&tiles_or_exit(&file), days)),
+        Command::Populations { file, days } => {
+            for (day, floor) in floor::floors(tiles_or_exit(&file), days).enumerate() {
+                println!("{} {}", day, floor.len());
+            }
+        },
+        Command::Tiles { file, days } => {
+            let mut tiles: Vec<Pos> = floor::floor_after(tiles_or_exit(&file), days).into_iter().collect();
+            tiles.sort();
+            for p in tiles {
+                println!("{} {}", p.x, p.y);
+            }
+        },
+        Command::Svg { file, directory, days } => {
+            let floors = living_art(tiles_or_exit(&file), days);
+            match day_24::svg::write_days(&floors, &directory) {
+                Ok(files) => println!("Wrote {} files to {}", files.len(), directory),
+                Err(e) => {
+                    eprintln!("{}: {}", directory, e);
+                    process::exit(1);
+                },
+            }
+        },
     }
-    tiles.len()
-}
-
-fn main() {
-    println!("Part 1 sample: {:?}", run_part1("input/day24-sample.txt"));
-    println!("Part 2 sample: {:?}", run_part2("input/day24-sample.txt"));
-    println!("Part 1: {:?}", run_part1("input/day24-input.txt"));
-    println!("Part 2: {:?}", run_part2("input/day24-input.txt"));
 }
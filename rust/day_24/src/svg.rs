@@ -0,0 +1,140 @@
+//! Drawing the lobby floor as SVG.
+//!
+//! Each black tile is drawn as a hexagon with its point at the top,
+//! so that east is to the right and the rows of tiles are level.
+//! North is up.  White tiles are left as background.
+//!
+//! A run of the living art can be written as one file per day.  All
+//! of the files use the same view box, so they line up when they are
+//! made into an animation.
+
+use std::collections;
+use std::fs;
+use std::io;
+use std::path;
+
+use crate::hex::Pos;
+
+/// The distance from the center of a hexagon to a corner, in SVG units.
+const SIZE: f64 = 10.0;
+
+/// The part of the plane an SVG file shows.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ViewBox {
+    pub min_x: f64,
+    pub min_y: f64,
+    pub width: f64,
+    pub height: f64,
+}
+
+impl ViewBox {
+    /// A view box that holds every tile in all of the floors, with a
+    /// margin of one tile around the edge.
+    pub fn around<'a, I: IntoIterator<Item = &'a collections::HashSet<Pos>>>(floors: I) -> ViewBox {
+        let centers: Vec<(f64, f64)> = floors.into_iter().flat_map(|f| f.iter().map(|&p| center(p))).collect();
+        if centers.is_empty() {
+            return ViewBox { min_x: -2.0 * SIZE, min_y: -2.0 * SIZE, width: 4.0 * SIZE, height: 4.0 * SIZE };
+        }
+        let min_x = centers.iter().map(|c| c.0).fold(f64::INFINITY, f64::min) - 2.0 * SIZE;
+        let min_y = centers.iter().map(|c| c.1).fold(f64::INFINITY, f64::min) - 2.0 * SIZE;
+        let max_x = centers.iter().map(|c| c.0).fold(f64::NEG_INFINITY, f64::max) + 2.0 * SIZE;
+        let max_y = centers.iter().map(|c| c.1).fold(f64::NEG_INFINITY, f64::max) + 2.0 * SIZE;
+        ViewBox { min_x, min_y, width: max_x - min_x, height: max_y - min_y }
+    }
+}
+
+/// The center of a tile in SVG coordinates, where y goes down.
+fn center(p: Pos) -> (f64, f64) {
+    let x = SIZE * 3f64.sqrt() * (p.x as f64 + p.y as f64 / 2.0);
+    let y = -SIZE * 1.5 * p.y as f64;
+    (x, y)
+}
+
+/// The corners of a tile's hexagon, as an SVG list of points.
+fn corners(p: Pos) -> String {
+    let (cx, cy) = center(p);
+    (0..6)
+        .map(|i| {
+            let angle = (30.0 + 60.0 * i as f64).to_radians();
+            format!("{:.2},{:.2}", cx + SIZE * angle.cos(), cy + SIZE * angle.sin())
+        })
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Writes an SVG picture of the black tiles.
+pub fn write_svg<W: io::Write>(tiles: &collections::HashSet<Pos>, view: &ViewBox, out: &mut W) -> io::Result<()> {
+    writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{:.2} {:.2} {:.2} {:.2}" width="{:.0}" height="{:.0}">"#,
+        view.min_x, view.min_y, view.width, view.height, view.width, view.height
+    )?;
+    writeln!(
+        out,
+        r#"<rect x="{:.2}" y="{:.2}" width="{:.2}" height="{:.2}" fill="white"/>"#,
+        view.min_x, view.min_y, view.width, view.height
+    )?;
+    let mut sorted: Vec<Pos> = tiles.iter().cloned().collect();
+    sorted.sort();
+    for p in sorted {
+        writeln!(out, r#"<polygon points="{}" fill="black" stroke="gray" stroke-width="1"/>"#, corners(p))?;
+    }
+    writeln!(out, "</svg>")
+}
+
+/// Writes one SVG file per day into the directory, named `day-000.svg`
+/// and so on, all with the same view box.  Returns the file names.
+pub fn write_days<P: AsRef<path::Path>>(
+    days: &[collections::HashSet<Pos>],
+    directory: P
+) -> io::Result<Vec<path::PathBuf>> {
+    fs::create_dir_all(directory.as_ref())?;
+    let view = ViewBox::around(days);
+    let mut result = Vec::new();
+    for (day, tiles) in days.iter().enumerate() {
+        let file_name = directory.as_ref().join(format!("day-{:03}.svg", day));
+        let mut out = io::BufWriter::new(fs::File::create(&file_name)?);
+        write_svg(tiles, &view, &mut out)?;
+        result.push(file_name);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+use crate::hex::pos;
+
+#[test]
+fn test_centers() {
+    assert_eq!(center(pos(0, 0)), (0.0, 0.0));
+    let (x, y) = center(pos(1, 0));
+    assert!((x - SIZE * 3f64.sqrt()).abs() < 1e-9 && y == 0.0);
+    // Northeast is up and to the right, half a tile over.
+    let (x, y) = center(pos(0, 1));
+    assert!((x - SIZE * 3f64.sqrt() / 2.0).abs() < 1e-9 && y == -1.5 * SIZE);
+}
+
+#[test]
+fn test_write_svg() {
+    let tiles: collections::HashSet<Pos> = [pos(0, 0), pos(1, 0)].iter().cloned().collect();
+    let view = ViewBox::around(std::slice::from_ref(&tiles));
+    let mut out = Vec::new();
+    write_svg(&tiles, &view, &mut out).unwrap();
+    let text = String::from_utf8(out).unwrap();
+    assert!(text.starts_with(r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="-20.00 -20.00 57.32 40.00""#));
+    assert_eq!(text.matches("<polygon").count(), 2);
+    assert!(text.contains(r#"<polygon points="8.66,5.00 0.00,10.00 -8.66,5.00 -8.66,-5.00 -0.00,-10.00 8.66,-5.00""#));
+    assert!(text.ends_with("</svg>\n"));
+}
+
+#[test]
+fn test_write_days() {
+    let directory = std::env::temp_dir().join(format!("day_24_svg_{}", std::process::id()));
+    let day_0: collections::HashSet<Pos> = [pos(0, 0)].iter().cloned().collect();
+    let day_1: collections::HashSet<Pos> = [pos(5, 5)].iter().cloned().collect();
+    let files = write_days(&[day_0, day_1], &directory).unwrap();
+    assert_eq!(files, vec![directory.join("day-000.svg"), directory.join("day-001.svg")]);
+    let first = fs::read_to_string(&files[0]).unwrap();
+    let second = fs::read_to_string(&files[1]).unwrap();
+    assert_eq!(first.lines().next(), second.lines().next());
+    fs::remove_dir_all(&directory).unwrap();
+}
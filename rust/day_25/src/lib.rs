@@ -0,0 +1,91 @@
+//! Day 25: Combo Breaker.
+
+use std::error;
+
+use aoc_solution::{Answer, Solution};
+
+fn transform(subject_number: usize, loop_size: usize) -> usize {
+    let mut value = 1;
+    for _ in 0..loop_size {
+        value *= subject_number;
+        value %= 20201227;
+    }
+    value
+}
+
+fn find_loop_size(subject_number: usize, public_key: usize) -> usize {
+    let mut loop_size = 0;
+    let mut value = 1;
+    loop {
+        if value == public_key {
+            return loop_size
+        }
+        value *= subject_number;
+        value %= 20201227;
+        loop_size += 1;
+    }
+}
+
+#[test]
+fn test_find_loop_size() {
+    assert_eq!(find_loop_size(7, 5764801), 8);
+    assert_eq!(find_loop_size(7, 17807724), 11);
+}
+
+fn find_encryption_key(public_key_1: usize, public_key_2: usize) -> usize {
+    let loop_size_1 = find_loop_size(7, public_key_1);
+    transform(public_key_2, loop_size_1)
+}
+
+#[test]
+fn test_find_encryption_key() {
+    assert_eq!(find_encryption_key(5764801, 17807724), 14897079);
+    assert_eq!(find_encryption_key(17807724, 5764801), 14897079);
+}
+
+/// The public keys of the card and the door.
+pub struct PublicKeys(usize, usize);
+
+/// Parses a public key, which has to be a number that 7 can be
+/// transformed into: anything from 1 up to the modulus.
+fn parse_public_key(line: &aoc_common::Line) -> Result<usize, aoc_common::InputError> {
+    let key: usize = line.parse()?;
+    if key == 0 || 20201227 <= key {
+        return Err(line.invalid(format!("public key {} is not from 1 to 20201226", key)));
+    }
+    Ok(key)
+}
+
+#[test]
+fn test_parse_public_key() {
+    let parse = |text| parse_public_key(&aoc_common::Line { number: 2, text });
+    assert_eq!(parse("5764801").unwrap(), 5764801);
+    assert_eq!(parse("0").unwrap_err().to_string(), "line 2: public key 0 is not from 1 to 20201226");
+    assert_eq!(parse("20201227").unwrap_err().to_string(), "line 2: public key 20201227 is not from 1 to 20201226");
+}
+
+pub struct Day25;
+
+impl Solution for Day25 {
+    type Input = PublicKeys;
+    const DAY: u32 = 25;
+    const TITLE: &'static str = "Combo Breaker";
+
+    /// The input is the two public keys, one per line.
+    fn parse(&self, text: &str) -> Result<PublicKeys, Box<dyn error::Error>> {
+        let keys: Vec<aoc_common::Line> = aoc_common::lines(text).collect();
+        if keys.len() != 2 {
+            return Err(format!("expected 2 public keys, found {}", keys.len()).into());
+        }
+        Ok(PublicKeys(parse_public_key(&keys[0])?, parse_public_key(&keys[1])?))
+    }
+
+    fn part1(&self, input: &PublicKeys) -> Answer {
+        Answer::from(find_encryption_key(input.0, input.1))
+    }
+
+    /// There's no puzzle for part 2 on the last day.
+    fn part2(&self, _input: &PublicKeys) -> Answer {
+        Answer::None
+    }
+}
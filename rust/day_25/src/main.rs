@@ -0,0 +1,3 @@
+fn main() {
+    aoc_solution::main(day_25::Day25, "input/day25-input.txt");
+}